@@ -0,0 +1,32 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+/* single blocks: the cost of a block grows roughly quadratically with n.
+ * Since the 2^n and 5^(n-1) factors of the odd primes were folded into one
+ * pow_mod call, positions 1, 1000 and 10000 make 17, 395 and 2917 calls
+ * instead of 33, 790 and 5834 (see pow_mod_calls in tests/modmath.rs) */
+fn calc_digits(c: &mut Criterion) {
+    let mut group = c.benchmark_group("calc_digits");
+    group.sample_size(10);
+    for &n in &[1, 1_000, 10_000] {
+        group.bench_with_input(BenchmarkId::from_parameter(n), &n, |b, &n| {
+            b.iter(|| parpi::calc_digits(black_box(n)))
+        });
+    }
+    group.finish();
+}
+
+/* every block up to the given count, on all threads; 10000 digits takes
+ * minutes per iteration even on several cores */
+fn pi_string(c: &mut Criterion) {
+    let mut group = c.benchmark_group("pi_string");
+    group.sample_size(10);
+    for &digits in &[1_000, 10_000] {
+        group.bench_with_input(BenchmarkId::from_parameter(digits), &digits, |b, &d| {
+            b.iter(|| parpi::pi_string(black_box(d)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, calc_digits, pi_string);
+criterion_main!(benches);
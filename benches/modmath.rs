@@ -0,0 +1,31 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use parpi::modmath::{inv_mod, inv_mod2, mul_mod, pow_mod};
+
+/* a modulus of the size calc_digits uses around 10000 digits: 3^10 */
+const M: i32 = 59_049;
+/* the largest moduli allowed, just below 2^30 */
+const BIG: i32 = (1 << 30) - 35;
+
+fn modmath(c: &mut Criterion) {
+    c.bench_function("mul_mod", |b| {
+        b.iter(|| {
+            mul_mod(
+                black_box(123_456_789),
+                black_box(987_654_321),
+                black_box(BIG),
+            )
+        })
+    });
+    c.bench_function("pow_mod", |b| {
+        b.iter(|| pow_mod(black_box(10), black_box(9_999), black_box(M)))
+    });
+    c.bench_function("inv_mod", |b| {
+        b.iter(|| inv_mod(black_box(12_345), black_box(M)))
+    });
+    c.bench_function("inv_mod2", |b| {
+        b.iter(|| inv_mod2(black_box(12_345), black_box(M)))
+    });
+}
+
+criterion_group!(benches, modmath);
+criterion_main!(benches);
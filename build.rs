@@ -0,0 +1,5 @@
+fn main() {
+    /* node addons leave the N-API symbols to be resolved by node itself */
+    #[cfg(feature = "node")]
+    napi_build::setup();
+}
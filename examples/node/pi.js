@@ -0,0 +1,15 @@
+// Build the addon and run this with:
+//
+//   cargo build --release --lib --no-default-features --features node,rayon
+//   cp target/release/libparpi.so examples/node/parpi.node
+//   node examples/node/pi.js
+//
+// (the library is parpi.dll on Windows and libparpi.dylib on macOS)
+
+const parpi = require("./parpi.node");
+
+console.log(parpi.pi(50));
+
+parpi.piAsync(1000).then((digits) => {
+  console.log(digits.slice(0, 20) + "...");
+});
@@ -0,0 +1,28 @@
+/*
+ * Feed arbitrary strings to the digit-count parser. Run with
+ *
+ *     mkdir -p fuzz/corpus/parse_count
+ *     cargo +nightly fuzz run parse_count fuzz/corpus/parse_count \
+ *         fuzz/seeds/parse_count
+ *
+ * from the repository root. The corpus libFuzzer grows is left out of git;
+ * fuzz/seeds holds a handful of inputs at the edges of the grammar and of
+ * MAX_DIGITS to start it from.
+ */
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use parpi::{parse_count, ParpiError, MAX_DIGITS};
+
+fuzz_target!(|s: &str| {
+    match parse_count(s) {
+        /* an accepted count is computable and prints back to itself */
+        Ok(n) => {
+            assert!(n <= MAX_DIGITS);
+            assert_eq!(parse_count(&n.to_string()), Ok(n));
+        }
+        Err(ParpiError::InvalidCount(t)) | Err(ParpiError::CountTooLarge(t)) => assert_eq!(t, s),
+        Err(e) => panic!("unexpected error {:?}", e),
+    }
+});
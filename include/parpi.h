@@ -0,0 +1,20 @@
+/* C interface to parpi, built with `cargo build --release --features ffi`
+ * as libparpi.so / libparpi.a */
+
+#ifndef PARPI_H
+#define PARPI_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+/* Fill buf with len ASCII digits of pi, "3" followed by the fractional
+ * digits without a decimal point, and return the number of bytes written.
+ * The buffer belongs to the caller and is not NUL-terminated. Returns 0 and
+ * leaves the buffer alone if buf is NULL or len is 0 or too large. */
+size_t parpi_fill(uint8_t *buf, size_t len);
+
+/* Return the digit of pi at index, where 0 is the first digit after the
+ * decimal point, or 255 if the index is out of range. */
+uint8_t parpi_digit_at(uint32_t index);
+
+#endif
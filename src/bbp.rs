@@ -0,0 +1,52 @@
+/*
+ * Hexadecimal digits of pi through the Bailey-Borwein-Plouffe formula
+ *
+ * pi = sum( 1/16^k * (4/(8k+1) - 2/(8k+4) - 1/(8k+5) - 1/(8k+6)), k=0..infinity);
+ *
+ * Multiplying by 16^n and dropping the integer part of the terms with
+ * k <= n leaves fractions whose numerators are powers of 16 modulo the
+ * denominator, so the digits at position n come out of O(n) modular
+ * exponentiations, the same way calc_digits works for decimal digits. The
+ * two methods share nothing but pow_mod, which makes this an independent
+ * check on the decimal output.
+ */
+
+use crate::float;
+use crate::modmath::pow_mod;
+use core::convert::TryFrom;
+
+/* fractional part of sum( 16^(n-k) / (8k+j), k=0..infinity) */
+fn series(j: i32, n: i32) -> f64 {
+    let mut s = 0.0;
+    for k in 0..=n {
+        let d = 8 * k + j;
+        s = float::fract(s + pow_mod(16, n - k, d) as f64 / d as f64);
+    }
+    /* the terms with k > n are below 16^(n-k) and vanish quickly */
+    let mut scale = 1.0 / 16.0;
+    let mut k = n + 1;
+    while scale > 1e-17 {
+        s += scale / (8 * k + j) as f64;
+        scale /= 16.0;
+        k += 1;
+    }
+    float::fract(s)
+}
+
+/// Return the hexadecimal digit of pi at `index`, where index 0 is the
+/// first digit after the point, so that pi = 3.243F6A88... in hex.
+///
+/// Like [`calc_digits`](crate::calc_digits) this accumulates in `f64`,
+/// which leaves about ten correct hex digits at any position, plenty for
+/// the one that is returned. `index` must be below 2^28 so that the
+/// moduli fit in an `i32`.
+pub fn hex_digit_at(index: u32) -> u8 {
+    let n = i32::try_from(index)
+        .ok()
+        .filter(|&n| n < 1 << 28)
+        .expect("hex digit index beyond 2^28");
+    let x = 4.0 * series(1, n) - 2.0 * series(4, n) - series(5, n) - series(6, n);
+    let frac = float::fract(x);
+    let frac = if frac < 0.0 { frac + 1.0 } else { frac };
+    (frac * 16.0) as u8
+}
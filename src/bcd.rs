@@ -0,0 +1,83 @@
+/*
+ * Packed binary-coded decimal, two digits per byte, as written by
+ * `parpi --format bcd`.
+ */
+
+use std::io::{self, Write};
+
+/// Writer packing the ASCII digits written to it into BCD bytes.
+///
+/// Every byte holds two decimal digits, the first in the high nibble, so
+/// `14` becomes `0x14`. If the number of digits is odd, the low nibble of
+/// the last byte is `0xF`, which never occurs otherwise. The first `n`
+/// digits therefore take `(n + 1) / 2` bytes; the CLI packs pi with its
+/// leading `3` and without the decimal point, so `3.1415` is stored as
+/// `31 41 5F`.
+///
+/// Call [`finish`](BcdWriter::finish) after the last digit so that a
+/// pending odd digit is written with its padding nibble. Writing anything
+/// other than ASCII digits fails with [`io::ErrorKind::InvalidInput`].
+#[derive(Debug)]
+pub struct BcdWriter<W: Write> {
+    inner: W,
+    high: Option<u8>,
+}
+
+impl<W: Write> BcdWriter<W> {
+    /// Pack digits into `inner`.
+    pub fn new(inner: W) -> Self {
+        BcdWriter { inner, high: None }
+    }
+
+    /// Write out a pending odd digit, flush, and return the inner writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        if let Some(high) = self.high.take() {
+            self.inner.write_all(&[high << 4 | 0xF])?;
+        }
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write> Write for BcdWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if !buf.iter().all(u8::is_ascii_digit) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "only digits can be packed as BCD",
+            ));
+        }
+        let mut packed = Vec::with_capacity(buf.len() / 2 + 1);
+        for &c in buf {
+            let d = c - b'0';
+            match self.high.take() {
+                Some(high) => packed.push(high << 4 | d),
+                None => self.high = Some(d),
+            }
+        }
+        self.inner.write_all(&packed)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Unpack BCD bytes into a string of ASCII digits, or return `None` if a
+/// nibble is not a decimal digit, other than the padding in the low nibble
+/// of the last byte.
+pub fn decode(bytes: &[u8]) -> Option<String> {
+    let mut s = String::with_capacity(2 * bytes.len());
+    for (i, &b) in bytes.iter().enumerate() {
+        let (high, low) = (b >> 4, b & 0xF);
+        if high > 9 || (low > 9 && (low != 0xF || i + 1 != bytes.len())) {
+            return None;
+        }
+        s.push((b'0' + high) as char);
+        if low <= 9 {
+            s.push((b'0' + low) as char);
+        }
+    }
+    Some(s)
+}
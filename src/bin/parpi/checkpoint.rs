@@ -0,0 +1,70 @@
+/*
+ * Checkpoints for --resume.
+ *
+ * Every block of the output file has a fixed offset, so all a checkpoint
+ * needs to record is how many leading blocks are known to be on disk. It
+ * is a single line `LAYOUT BLOCKS`, where LAYOUT describes everything that
+ * decides the offsets or how the digits are computed, so that a checkpoint
+ * left by a run with other options is not mistaken for this one. A checkpoint that is missing,
+ * cannot be parsed or has another layout counts as no blocks at all, and
+ * the output is computed from the start.
+ */
+
+use clap::ValueEnum;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::Args;
+
+pub(crate) struct Checkpoint {
+    path: PathBuf,
+    layout: String,
+}
+
+impl Checkpoint {
+    pub(crate) fn new(path: &Path, digits: u32, args: &Args) -> Self {
+        let format = args
+            .format
+            .to_possible_value()
+            .expect("no skipped variants");
+        let layout = format!(
+            "parpi-checkpoint digits={} format={} group={} prefix={} exact={}",
+            digits,
+            format.get_name(),
+            args.group.unwrap_or(0),
+            !args.no_prefix,
+            args.exact
+        );
+        Checkpoint {
+            path: path.to_path_buf(),
+            layout,
+        }
+    }
+
+    /* return the number of leading blocks already written, at most
+     * num_blocks */
+    pub(crate) fn load(&self, num_blocks: usize) -> usize {
+        let Ok(text) = fs::read_to_string(&self.path) else {
+            return 0;
+        };
+        let blocks = text
+            .trim_end()
+            .strip_prefix(&self.layout)
+            .and_then(|rest| rest.strip_prefix(' '))
+            .and_then(|n| n.parse().ok());
+        match blocks {
+            Some(n) if n <= num_blocks => n,
+            _ => 0,
+        }
+    }
+
+    /* record that the first `blocks` blocks are written; the checkpoint is
+     * replaced by a rename, so a crash leaves either the old or the new */
+    pub(crate) fn save(&self, blocks: usize) -> io::Result<()> {
+        let mut tmp = self.path.clone().into_os_string();
+        tmp.push(".tmp");
+        fs::write(&tmp, format!("{} {}\n", self.layout, blocks))?;
+        fs::rename(&tmp, &self.path)
+    }
+}
@@ -0,0 +1,50 @@
+/*
+ * Defaults read from a TOML file, by default parpi.toml in the user's
+ * configuration directory:
+ *
+ *     threads = 4
+ *     format = "raw"
+ *
+ * Options given on the command line or through the environment take
+ * precedence over the file, which takes precedence over the built-in
+ * defaults.
+ */
+
+use serde::Deserialize;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Config {
+    pub(crate) threads: Option<u32>,
+    pub(crate) format: Option<String>,
+}
+
+/* $XDG_CONFIG_HOME/parpi.toml, or ~/.config/parpi.toml */
+fn default_path() -> Option<PathBuf> {
+    let dir = match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+    };
+    Some(dir.join("parpi.toml"))
+}
+
+/* read the file at `path`, or at the default path if there is one; only a
+ * file given explicitly has to exist */
+pub(crate) fn load(path: Option<&Path>) -> Result<Config, String> {
+    let (path, required) = match path {
+        Some(path) => (path.to_path_buf(), true),
+        None => match default_path() {
+            Some(path) => (path, false),
+            None => return Ok(Config::default()),
+        },
+    };
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound && !required => return Ok(Config::default()),
+        Err(e) => return Err(format!("{}: {}", path.display(), e)),
+    };
+    toml::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e.message()))
+}
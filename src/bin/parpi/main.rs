@@ -0,0 +1,1016 @@
+mod checkpoint;
+mod config;
+mod mapped;
+mod radix;
+mod reference;
+mod repl;
+mod serve;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::write::EncoderWriter;
+use clap::error::ErrorKind;
+use clap::parser::ValueSource;
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use parpi::bcd::BcdWriter;
+use parpi::{ParpiError, PiComputer, PiResult};
+use reference::Reference;
+use sha2::{Digest, Sha256};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Compute digits of pi in parallel.
+#[derive(Parser)]
+#[command(version, about, args_conflicts_with_subcommands = true)]
+struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Number of digits to compute, such as 1000, 10K or 2M, or "-" to read
+    /// it from stdin. Several counts print one line each, labeled with the
+    /// count
+    #[arg(default_value = "800", value_parser = parse_digits, num_args = 1..)]
+    digits: Vec<u32>,
+
+    /// Read default options from this TOML file instead of parpi.toml in
+    /// $XDG_CONFIG_HOME or ~/.config. It may set `threads` and `format`;
+    /// options given on the command line or through the environment win
+    #[arg(long, global = true, value_name = "PATH")]
+    config: Option<PathBuf>,
+
+    /// Number of worker threads [default: one per core]
+    #[arg(
+        long,
+        global = true,
+        env = "PARPI_THREADS",
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    threads: Option<u32>,
+
+    /// Number of consecutive nine-digit blocks each parallel task computes
+    /// [default: one per task, or as rayon splits the work for --output].
+    /// Blocks get slower with their position, so large chunks leave threads
+    /// idle near the end of a run, and with fewer chunks than --threads some
+    /// threads get no work at all
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    chunk_size: Option<u32>,
+
+    /// Most blocks computed or waiting to be written at once while the
+    /// digits stream out [default: four per thread]. Blocks that finish
+    /// early wait until every block before them is written, so a small
+    /// window bounds the memory held but lets a slow block stall the
+    /// threads
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    window: Option<u32>,
+
+    /// Sum each block exactly in fixed point instead of in floating point,
+    /// so that digits past the first 50000 are right too. No effect with
+    /// --base 2 or 16, whose digits come from the BBP formula
+    #[arg(long, global = true)]
+    exact: bool,
+
+    /// Write the digits to this file instead of stdout. A regular file is
+    /// sized up front and written through a memory map, while a FIFO is
+    /// streamed to block by block
+    #[arg(long, value_name = "PATH")]
+    output: Option<PathBuf>,
+
+    /// Record in FILE how many blocks of --output are done, and on a later
+    /// run with the same options continue from there instead of starting
+    /// over
+    #[arg(long, value_name = "FILE", requires = "output")]
+    resume: Option<PathBuf>,
+
+    /// Compress the --output file with gzip. The digits are streamed
+    /// through the encoder, so the file cannot be resumed
+    #[arg(long, requires = "output", conflicts_with = "resume")]
+    compress: bool,
+
+    /// Flush every block to stdout as soon as it is ready instead of
+    /// buffering the output
+    #[arg(long, conflicts_with = "output")]
+    stream: bool,
+
+    /// Base of the digits: 10, 16 or 2. Bases other than 10 only go to
+    /// stdout, in any format but jsonl, bcd and base64
+    #[arg(
+        long,
+        default_value_t = 10,
+        value_parser = clap::value_parser!(u32).range(2..=36),
+        conflicts_with_all = ["output", "verify", "check", "serve"]
+    )]
+    base: u32,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = Format::Decimal)]
+    format: Format,
+
+    /// Leave out the leading "3." in every format
+    #[arg(long)]
+    no_prefix: bool,
+
+    /// Insert a space after every N digits following the decimal point
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    group: Option<u32>,
+
+    /// Color alternate groups of digits, or every other ten digits without
+    /// --group. Applies to decimal and raw output on stdout; auto colors
+    /// only a terminal, and not if NO_COLOR is set
+    #[arg(long, value_enum, value_name = "WHEN", default_value_t = Color::Auto)]
+    color: Color,
+
+    /// Show a progress bar on stderr
+    #[arg(long)]
+    progress: bool,
+
+    /// Compare the first 1000 digits against a built-in reference and fail
+    /// on the first mismatch
+    #[arg(long)]
+    verify: bool,
+
+    /// Compare the digits against those in FILE as they are written, and
+    /// fail on the first mismatch. FILE holds digits after the decimal
+    /// point, optionally preceded by "3.", and may contain whitespace; only
+    /// as many digits as it holds are compared
+    #[arg(long, value_name = "FILE", conflicts_with = "verify")]
+    check: Option<PathBuf>,
+
+    /// Stop each digit count after SECONDS and print the digits of every
+    /// block done by then, in order. The digit count is still the most
+    /// printed, so give a large one such as 10M. The clock is only checked
+    /// between blocks, so a block started in time may run past the budget.
+    /// With 0 no block is printed, just the 3
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        value_parser = parse_seconds,
+        conflicts_with_all = ["output", "serve", "sum"]
+    )]
+    time: Option<Duration>,
+
+    /// Print to stderr how large the computation is: the number of blocks,
+    /// and for the last and costliest of them the terms of the series, the
+    /// primes summed over and the largest prime power modulus
+    #[arg(long, conflicts_with = "base")]
+    stats: bool,
+
+    /// Print the wall-clock time of the computation to stderr
+    #[arg(long)]
+    timing: bool,
+
+    /// Compute the digits without printing them, and report the time taken
+    /// and the digits per second on stderr
+    #[arg(long, conflicts_with_all = ["output", "stream"])]
+    benchmark: bool,
+
+    /// Print the sum of the digits after the decimal point instead of the
+    /// digits themselves. Ctrl-C stops the computation without printing a
+    /// sum
+    #[arg(
+        long,
+        conflicts_with_all = ["output", "stream", "base", "format", "group", "progress", "verify", "check", "benchmark"]
+    )]
+    sum: bool,
+
+    /// Print the SHA-256 of the digits instead of the digits themselves.
+    /// The hash covers exactly the bytes --format raw prints, without the
+    /// newline. Ctrl-C stops the computation without printing a hash
+    #[arg(
+        long,
+        conflicts_with_all = ["output", "stream", "format", "group", "sum", "benchmark"]
+    )]
+    sha256: bool,
+
+    /// Instead of printing digits, listen on ADDR for TCP connections that
+    /// request digit ranges as "START COUNT" lines
+    #[arg(long, value_name = "ADDR", conflicts_with_all = ["output", "stream", "benchmark", "sum", "sha256"])]
+    serve: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Read positions after the decimal point, such as 1000, or ranges such
+    /// as 1000-1010 from stdin and print the digits there
+    Repl,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// 3.14159...
+    Decimal,
+    /// Fractional digits only: 14159...
+    Raw,
+    /// {"digits":"3.14159...","count":N}
+    Json,
+    /// One {"index": 0, "digits": "141592653"} line per nine-digit block
+    Jsonl,
+    /// Packed BCD, two digits per byte starting with the 3; an odd last
+    /// digit is padded with 0xF
+    Bcd,
+    /// Rust source: const DIGITS: &[u8] = b"3.14159...";
+    Rust,
+    /// A C header: static const char DIGITS[] = "3.14159...";
+    CHeader,
+    /// The raw format's digits in standard base64 on one line; `base64 -d`
+    /// turns it back into 14159...
+    Base64,
+    /// The json format's object as a MessagePack map
+    #[cfg(feature = "msgpack")]
+    Msgpack,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Color {
+    Auto,
+    Always,
+    Never,
+}
+
+/* the two colors that groups of digits alternate between */
+const GROUP_COLORS: [&[u8]; 2] = [b"\x1b[36m", b"\x1b[33m"];
+const RESET_COLOR: &[u8] = b"\x1b[0m";
+
+/* parse the digit count, reading it from stdin when given as "-" */
+fn parse_digits(s: &str) -> Result<u32, ParpiError> {
+    if s != "-" {
+        return parpi::parse_count(s);
+    }
+    let mut line = String::new();
+    io::stdin().read_line(&mut line)?;
+    parpi::parse_count(line.trim())
+}
+
+fn parse_seconds(s: &str) -> Result<Duration, String> {
+    s.parse::<f64>()
+        .ok()
+        .filter(|&t| t >= 0.0)
+        .and_then(|t| Duration::try_from_secs_f64(t).ok())
+        .ok_or_else(|| format!("{:?} is not a number of seconds", s))
+}
+
+/* splits the bytes written through it into groups of `size`, with a space
+ * between groups if `space` and switching between GROUP_COLORS at the start
+ * of every group if `color` */
+struct Grouped<W> {
+    inner: W,
+    size: usize,
+    count: usize,
+    space: bool,
+    color: bool,
+    /* index of the current group */
+    group: usize,
+}
+
+impl<W: Write> Write for Grouped<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut rest = buf;
+        while !rest.is_empty() {
+            if self.count == self.size {
+                if self.space {
+                    self.inner.write_all(b" ")?;
+                }
+                self.count = 0;
+                self.group += 1;
+            }
+            if self.count == 0 && self.color {
+                self.inner.write_all(GROUP_COLORS[self.group % 2])?;
+            }
+            let n = rest.len().min(self.size - self.count);
+            self.inner.write_all(&rest[..n])?;
+            self.count += n;
+            rest = &rest[n..];
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/* what was seen of the digits while they were written */
+#[derive(Default)]
+struct Tally {
+    written: AtomicUsize,
+    mismatch: OnceLock<usize>,
+    /* the rest of the digits to compare against, until they run out or
+     * one of them does not match */
+    reference: Mutex<Option<Reference>>,
+}
+
+impl Tally {
+    /* compare digits starting at index `start` to the reference */
+    fn compare(&self, start: usize, digits: &[u8]) -> io::Result<()> {
+        let mut reference = self.reference.lock().unwrap();
+        let Some(expected) = reference.as_mut() else {
+            return Ok(());
+        };
+        for (i, &c) in digits.iter().enumerate() {
+            match expected.next().transpose()? {
+                Some(e) if e == c => {}
+                Some(_) => {
+                    let _ = self.mismatch.set(start + i);
+                    *reference = None;
+                    break;
+                }
+                None => {
+                    *reference = None;
+                    break;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/* counts the digits written through it and compares them to the reference */
+struct Tap<'a, W> {
+    inner: W,
+    tally: &'a Tally,
+}
+
+impl<W: Write> Write for Tap<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        let start = self.tally.written.fetch_add(n, Ordering::Relaxed);
+        self.tally.compare(start, &buf[..n])?;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/* set by the first Ctrl-C */
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::Relaxed)
+}
+
+/* fails every write once `at` has passed or Ctrl-C was pressed; as every
+ * write is one or more whole blocks, what got through is an untorn prefix
+ * of the digits */
+struct Cutoff<W> {
+    inner: W,
+    at: Option<Instant>,
+    cut: bool,
+}
+
+impl<W: Write> Write for Cutoff<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if interrupted() || self.at.is_some_and(|at| Instant::now() >= at) {
+            self.cut = true;
+            return Err(io::Error::other("cut off"));
+        }
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/* writes `head` just before the first digits */
+struct HeadFirst<W> {
+    inner: W,
+    head: Option<String>,
+}
+
+impl<W: Write> Write for HeadFirst<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if let Some(head) = self.head.take() {
+            self.inner.write_all(head.as_bytes())?;
+        }
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/* flushes after every write, so that each block reaches the reader at once */
+struct Flushing<W>(W);
+
+impl<W: Write> Write for Flushing<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.0.write(buf)?;
+        self.0.flush()?;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+/* write the digits through grouping and the tally, flushing after every
+ * block if `live` and stopping early once --time has passed or on Ctrl-C */
+fn write_digits<W: Write + Send>(
+    w: &mut W,
+    digits: u32,
+    args: &Args,
+    tally: &Tally,
+    live: bool,
+) -> io::Result<()> {
+    let color = use_color(args);
+    let default_size = if color { 10 } else { usize::MAX };
+    let grouped = Grouped {
+        inner: w,
+        size: args.group.map_or(default_size, |n| n as usize),
+        count: 0,
+        space: args.group.is_some(),
+        color,
+        group: 0,
+    };
+    let mut w = Cutoff {
+        inner: Tap {
+            inner: grouped,
+            tally,
+        },
+        at: args.time.map(|t| Instant::now() + t),
+        cut: false,
+    };
+    let result = match (live, args.base) {
+        (true, 10) => computer(digits, args).write_to(&mut Flushing(&mut w)),
+        (false, 10) => computer(digits, args).write_to(&mut w),
+        (true, base) => radix::write_digits_to(&mut Flushing(&mut w), digits, base),
+        (false, base) => radix::write_digits_to(&mut w, digits, base),
+    };
+    match result {
+        Err(_) if w.cut => {}
+        result => result?,
+    }
+    if color && tally.written.load(Ordering::Relaxed) > 0 {
+        w.inner.inner.inner.write_all(RESET_COLOR)?;
+    }
+    Ok(())
+}
+
+/* whether to color the digits, which only ever happens on stdout */
+fn use_color(args: &Args) -> bool {
+    let text = matches!(args.format, Format::Decimal | Format::Raw);
+    let wanted = match args.color {
+        Color::Always => true,
+        Color::Never => false,
+        Color::Auto => io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none(),
+    };
+    text && wanted && args.output.is_none()
+}
+
+/* return the text written before and after `digits` digits; with more
+ * than one count, each is labelled with the count `asked` for, however
+ * many digits --time or Ctrl-C let through */
+fn frame(asked: u32, digits: u32, args: &Args) -> (String, String) {
+    let prefix = match args.format {
+        Format::Decimal | Format::Json | Format::Rust | Format::CHeader if !args.no_prefix => {
+            radix::prefix(digits, args.base)
+        }
+        #[cfg(feature = "msgpack")]
+        Format::Msgpack if !args.no_prefix => radix::prefix(digits, args.base),
+        _ => String::new(),
+    };
+    match args.format {
+        Format::Decimal | Format::Raw if args.digits.len() > 1 => {
+            (format!("{}: {}", asked, prefix), "\n".to_string())
+        }
+        Format::Decimal | Format::Raw => (prefix, "\n".to_string()),
+        Format::Json => {
+            /* the digits are streamed into the string of an otherwise
+             * empty PiResult, so that the JSON is whatever serde makes of
+             * it; they need no escaping */
+            let empty = PiResult {
+                digits: String::new(),
+                count: digits,
+            };
+            let json = serde_json::to_string(&empty).expect("PiResult serializes");
+            let (head, tail) = json
+                .split_once("\"digits\":\"\"")
+                .expect("digits are serialized as a string");
+            (
+                format!("{}\"digits\":\"{}", head, prefix),
+                format!("\"{}\n", tail),
+            )
+        }
+        /* the count goes after the digits, where it can still be made to
+         * match what --time let through */
+        Format::Rust => (
+            format!("const DIGITS: &[u8] = b\"{}", prefix),
+            format!("\";\n// {} digits after the decimal point\n", digits),
+        ),
+        Format::CHeader => (
+            format!(
+                "#ifndef PARPI_DIGITS_H\n#define PARPI_DIGITS_H\n\nstatic const char DIGITS[] = \"{}",
+                prefix
+            ),
+            format!(
+                "\";\n/* {} digits after the decimal point */\n\n#endif\n",
+                digits
+            ),
+        ),
+        /* the prefix starts the digits string of the PiResult */
+        #[cfg(feature = "msgpack")]
+        Format::Msgpack => (prefix, String::new()),
+        Format::Jsonl | Format::Bcd | Format::Base64 => (String::new(), String::new()),
+    }
+}
+
+/* writes the digits passed through it as one JSON object per block */
+struct JsonLines<W> {
+    inner: W,
+    index: u32,
+    block: Vec<u8>,
+}
+
+impl<W: Write> JsonLines<W> {
+    fn new(inner: W) -> Self {
+        JsonLines {
+            inner,
+            index: 0,
+            block: Vec::with_capacity(9),
+        }
+    }
+
+    fn write_line(&mut self) -> io::Result<()> {
+        let digits = std::str::from_utf8(&self.block).expect("digits are ASCII");
+        writeln!(
+            self.inner,
+            "{{\"index\": {}, \"digits\": \"{}\"}}",
+            self.index, digits
+        )?;
+        self.index += 1;
+        self.block.clear();
+        Ok(())
+    }
+
+    /* write out the last block, which may be shorter than nine digits */
+    fn finish(&mut self) -> io::Result<()> {
+        if !self.block.is_empty() {
+            self.write_line()?;
+        }
+        self.inner.flush()
+    }
+}
+
+impl<W: Write> Write for JsonLines<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for &c in buf {
+            self.block.push(c);
+            if self.block.len() == 9 {
+                self.write_line()?;
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+fn write_pi<W: Write + Send>(
+    w: W,
+    digits: u32,
+    args: &Args,
+    tally: &Tally,
+    live: bool,
+) -> io::Result<()> {
+    let mut w = BufWriter::new(w);
+    if args.format == Format::Jsonl {
+        let mut lines = JsonLines::new(&mut w);
+        write_digits(&mut lines, digits, args, tally, live)?;
+        return lines.finish();
+    }
+    if args.format == Format::Bcd {
+        let mut bcd = BcdWriter::new(&mut w);
+        if !args.no_prefix {
+            bcd.write_all(b"3")?;
+        }
+        write_digits(&mut bcd, digits, args, tally, live)?;
+        return bcd.finish().map(drop);
+    }
+    /* MessagePack puts the length of the digits before them, so they are
+     * gathered first into the same PiResult the json format shows */
+    #[cfg(feature = "msgpack")]
+    if args.format == Format::Msgpack {
+        let mut text = Vec::new();
+        write_digits(&mut text, digits, args, tally, live)?;
+        let count = tally.written.load(Ordering::Relaxed) as u32;
+        let (head, _) = frame(digits, count, args);
+        let result = PiResult {
+            digits: head + std::str::from_utf8(&text).expect("digits are ASCII"),
+            count,
+        };
+        rmp_serde::encode::write_named(&mut w, &result).map_err(io::Error::other)?;
+        return w.flush();
+    }
+    if args.format == Format::Base64 {
+        let mut encoder = EncoderWriter::new(&mut w, &STANDARD);
+        write_digits(&mut encoder, digits, args, tally, live)?;
+        let w = encoder.finish()?;
+        w.write_all(b"\n")?;
+        return w.flush();
+    }
+    /* with --time or Ctrl-C fewer digits may be written than asked for,
+     * possibly none; the head goes out with the first of them, and the
+     * frame matches the digits that made it */
+    let (head, _) = frame(digits, digits, args);
+    let mut headed = HeadFirst {
+        inner: &mut w,
+        head: Some(head),
+    };
+    write_digits(&mut headed, digits, args, tally, live)?;
+    let written = tally.written.load(Ordering::Relaxed) as u32;
+    let (head, tail) = frame(digits, written, args);
+    if headed.head.is_some() {
+        w.write_all(head.as_bytes())?;
+    }
+    w.write_all(tail.as_bytes())?;
+    w.flush()
+}
+
+fn write_file(path: &Path, digits: u32, args: &Args, tally: &Tally) -> io::Result<()> {
+    if args.compress {
+        let f = File::create(path)?;
+        let mut gz = GzEncoder::new(f, Compression::default());
+        write_pi(&mut gz, digits, args, tally, false)?;
+        return gz.finish().map(drop);
+    }
+    /* FIFOs and devices can be neither sized nor mapped; they are streamed
+     * to instead, flushing every block so that a reader sees it at once */
+    if fs::metadata(path).is_ok_and(|m| !m.is_file()) {
+        let f = OpenOptions::new().write(true).open(path)?;
+        return write_pi(f, digits, args, tally, true);
+    }
+    let f = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(args.resume.is_none())
+        .open(path)?;
+    match args.format {
+        /* lines grow with the block index, so their offsets are not worth
+         * laying out in a map */
+        Format::Jsonl | Format::Bcd | Format::Base64 => write_pi(f, digits, args, tally, false),
+        #[cfg(feature = "msgpack")]
+        Format::Msgpack => write_pi(f, digits, args, tally, false),
+        _ => mapped::write_pi(&f, digits, args, tally),
+    }
+}
+
+/* the digits after the point to be computed by the library, split into
+ * tasks as --chunk-size, streamed as --window and summed as --exact ask */
+fn computer(digits: u32, args: &Args) -> PiComputer {
+    PiComputer::new()
+        .digits(digits)
+        .prefix(false)
+        .chunk_size(args.chunk_size.unwrap_or(0))
+        .window(args.window.unwrap_or(0))
+        .exact(args.exact)
+        .build()
+}
+
+/* a reader that goes away, such as `head`, just ends the output early;
+ * returns whether the reader is still there */
+fn reader_gone(e: io::Error) -> io::Result<bool> {
+    if e.kind() == io::ErrorKind::BrokenPipe {
+        Ok(false)
+    } else {
+        Err(e)
+    }
+}
+
+fn run(args: &Args) -> Result<(), ParpiError> {
+    /* zero lets rayon pick one thread per core */
+    let threads = args.threads.unwrap_or(0) as usize;
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .map_err(|e| ParpiError::ThreadPool(e.to_string()))?;
+
+    let computer = computer(0, args);
+
+    if let Some(Command::Repl) = args.command {
+        return Ok(repl::repl(&pool, &computer)?);
+    }
+
+    if let Some(addr) = &args.serve {
+        return serve::serve(addr, pool, computer)
+            .map_err(|e| ParpiError::Io(format!("{}: {}", addr, e)));
+    }
+
+    for &digits in &args.digits {
+        if !compute(digits, args, &pool)? || interrupted() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/* compute and write one digit count; returns false if the reader of the
+ * output went away */
+fn compute(digits: u32, args: &Args, pool: &rayon::ThreadPool) -> Result<bool, ParpiError> {
+    if args.base == 10 && !args.exact && digits > parpi::max_accurate_digits() {
+        eprintln!(
+            "parpi: warning: digits past the first {} may be inaccurate",
+            parpi::max_accurate_digits()
+        );
+    }
+
+    let tally = Tally::default();
+    if args.verify {
+        *tally.reference.lock().unwrap() = Some(Reference::builtin());
+    }
+    if let Some(path) = &args.check {
+        let reference = Reference::open(path)
+            .map_err(|e| ParpiError::Io(format!("{}: {}", path.display(), e)))?;
+        *tally.reference.lock().unwrap() = Some(reference);
+    }
+    let done = AtomicBool::new(false);
+    let start = Instant::now();
+    let present = thread::scope(|s| {
+        if args.progress {
+            s.spawn(|| show_progress(digits, &tally.written, &done));
+        }
+        let result = pool.install(|| match &args.output {
+            Some(path) => write_file(path, digits, args, &tally)
+                .map(|()| true)
+                .or_else(reader_gone)
+                .map_err(|e| ParpiError::Io(format!("{}: {}", path.display(), e))),
+            None if args.sum => Ok(write_sum(digits, args)
+                .map(|()| true)
+                .or_else(reader_gone)?),
+            None if args.sha256 => Ok(write_sha256(digits, args, &tally)
+                .map(|()| true)
+                .or_else(reader_gone)?),
+            None if args.benchmark => {
+                write_pi(io::sink(), digits, args, &tally, false)?;
+                Ok(true)
+            }
+            None => Ok(write_pi(io::stdout(), digits, args, &tally, args.stream)
+                .map(|()| true)
+                .or_else(reader_gone)?),
+        });
+        done.store(true, Ordering::Relaxed);
+        result
+    })?;
+    let elapsed = start.elapsed().as_secs_f64();
+    if args.timing {
+        eprintln!("computed {} digits in {:.2}s", digits, elapsed);
+    }
+    if args.stats {
+        print_stats(digits, args.exact);
+    }
+    if args.benchmark {
+        let threads = pool.current_num_threads();
+        eprintln!(
+            "{} digits on {} thread{} in {:.3}s: {:.0} digits/s",
+            digits,
+            threads,
+            if threads == 1 { "" } else { "s" },
+            elapsed,
+            digits as f64 / elapsed
+        );
+    }
+
+    match tally.mismatch.get() {
+        Some(&i) => Err(ParpiError::Mismatch(i)),
+        None => Ok(present),
+    }
+}
+
+/* write the digits as --format raw prints them to `w`, stopping early on
+ * Ctrl-C; returns `w`, or None if it did not get every digit */
+fn write_raw<W: Write + Send>(w: W, digits: u32, args: &Args) -> io::Result<Option<W>> {
+    let mut w = Cutoff {
+        inner: w,
+        at: None,
+        cut: false,
+    };
+    let result = match args.base {
+        10 => computer(digits, args).write_to(&mut w),
+        base => radix::write_digits_to(&mut w, digits, base),
+    };
+    match result {
+        Err(_) if w.cut => Ok(None),
+        result => result.map(|()| Some(w.inner)),
+    }
+}
+
+/* adds up the digits written through it */
+struct Summing(u64);
+
+impl Write for Summing {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.iter().map(|&d| (d - b'0') as u64).sum::<u64>();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/* print the sum of the digits, labeled like the digits when there are
+ * several counts; after Ctrl-C the sum of only some of them would pass for
+ * the whole, so nothing is printed */
+fn write_sum(digits: u32, args: &Args) -> io::Result<()> {
+    let Some(Summing(sum)) = write_raw(Summing(0), digits, args)? else {
+        return Ok(());
+    };
+    if args.digits.len() > 1 {
+        writeln!(io::stdout(), "{}: {}", digits, sum)
+    } else {
+        writeln!(io::stdout(), "{}", sum)
+    }
+}
+
+/* feeds the bytes written through it to a SHA-256 */
+struct Hashing(Sha256);
+
+impl Write for Hashing {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/* print the hex SHA-256 of the digits as --format raw writes them; like
+ * the sum, nothing after Ctrl-C */
+fn write_sha256(digits: u32, args: &Args, tally: &Tally) -> io::Result<()> {
+    let w = Tap {
+        inner: Hashing(Sha256::new()),
+        tally,
+    };
+    let Some(w) = write_raw(w, digits, args)? else {
+        return Ok(());
+    };
+    let digest = w.inner.0.finalize();
+    if args.digits.len() > 1 {
+        writeln!(io::stdout(), "{}: {:x}", digits, digest)
+    } else {
+        writeln!(io::stdout(), "{:x}", digest)
+    }
+}
+
+/* describe the work behind `digits` digits; each block sums over every
+ * prime up to three times its number of terms, so the last block, at the
+ * largest position, is the costliest. The exact sum gets them all right */
+fn print_stats(digits: u32, exact: bool) {
+    use parpi::modmath::{max_power, sieve};
+
+    let num_blocks = digits.div_ceil(9);
+    eprintln!("blocks: {}", num_blocks);
+    if num_blocks == 0 {
+        return;
+    }
+    let n = 9 * (num_blocks - 1) as i32 + 1;
+    let nl = parpi::num_terms(n);
+    let primes = sieve(3 * nl);
+    /* the power of 2 also carries the 2^(k-1) of the series */
+    let (vmax, a) = primes
+        .iter()
+        .map(|&a| (max_power(a, 3 * nl) + if a == 2 { nl - n } else { 0 }, a))
+        .max()
+        .expect("there are primes up to 3 * nl");
+    eprintln!("last block: digits {} to {}", n, n + 8);
+    eprintln!("terms: {}", nl);
+    eprintln!("primes: {} up to {}", primes.len(), 3 * nl);
+    eprintln!("largest modulus: {}^{}", a, vmax);
+    if exact {
+        eprintln!("accurate digits: {} of {}", digits, digits);
+    } else {
+        eprintln!(
+            "accurate digits: {} of {} (estimated)",
+            digits.min(parpi::max_accurate_digits()),
+            digits
+        );
+    }
+}
+
+/* redraw a progress bar on stderr until `done` is set */
+fn show_progress(digits: u32, written: &AtomicUsize, done: &AtomicBool) {
+    let bar = indicatif::ProgressBar::new(digits as u64);
+    while !done.load(Ordering::Relaxed) {
+        bar.set_position(written.load(Ordering::Relaxed) as u64);
+        thread::sleep(Duration::from_millis(100));
+    }
+    bar.finish_and_clear();
+}
+
+/* fill in the options the command line and environment left unset from
+ * the config file */
+fn apply_config(args: &mut Args, matches: &clap::ArgMatches) -> Result<(), String> {
+    let config = config::load(args.config.as_deref())?;
+    if let Some(threads) = config.threads {
+        if threads == 0 {
+            return Err("threads in the config file must be at least 1".to_string());
+        }
+        args.threads = args.threads.or(Some(threads));
+    }
+    if let Some(format) = config.format {
+        if matches.value_source("format") == Some(ValueSource::DefaultValue) {
+            args.format = Format::from_str(&format, false)
+                .map_err(|_| format!("unknown format '{}' in the config file", format))?;
+        }
+    }
+    Ok(())
+}
+
+fn main() {
+    let matches = Args::command().get_matches();
+    let mut args = Args::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    if let Err(e) = apply_config(&mut args, &matches) {
+        Args::command().error(ErrorKind::InvalidValue, e).exit();
+    }
+    if args.group.is_some() && matches!(args.format, Format::Jsonl | Format::Bcd | Format::Base64) {
+        Args::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--group cannot be used with --format jsonl, bcd or base64",
+            )
+            .exit();
+    }
+    if !radix::SUPPORTED.contains(&args.base) {
+        Args::command()
+            .error(
+                ErrorKind::InvalidValue,
+                format!(
+                    "base {} is not supported yet, only bases 2, 10 and 16 are",
+                    args.base
+                ),
+            )
+            .exit();
+    }
+    if args.base != 10 && matches!(args.format, Format::Jsonl | Format::Bcd | Format::Base64) {
+        Args::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--base cannot be used with --format jsonl, bcd or base64",
+            )
+            .exit();
+    }
+    if args.resume.is_some() && matches!(args.format, Format::Jsonl | Format::Bcd | Format::Base64)
+    {
+        Args::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--resume cannot be used with --format jsonl, bcd or base64",
+            )
+            .exit();
+    }
+    #[cfg(feature = "msgpack")]
+    if args.resume.is_some() && args.format == Format::Msgpack {
+        Args::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--resume cannot be used with --format msgpack",
+            )
+            .exit();
+    }
+    /* the source formats would define DIGITS once per count */
+    let single = matches!(
+        args.format,
+        Format::Jsonl | Format::Bcd | Format::Base64 | Format::Rust | Format::CHeader
+    );
+    if args.digits.len() > 1 && (args.output.is_some() || single) {
+        Args::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "several digit counts cannot be used with --output or --format jsonl, bcd, base64, rust or c-header",
+            )
+            .exit();
+    }
+
+    /* the first Ctrl-C stops the computation, and the digits done so far
+     * are still written out; a second one exits at once */
+    let handler = ctrlc::set_handler(|| {
+        if INTERRUPTED.swap(true, Ordering::Relaxed) {
+            std::process::exit(130);
+        }
+    });
+    if let Err(e) = handler {
+        eprintln!("parpi: warning: cannot handle Ctrl-C: {}", e);
+    }
+
+    if let Err(e) = run(&args) {
+        eprintln!("parpi: {}", e);
+        std::process::exit(1);
+    }
+    if interrupted() {
+        eprintln!("parpi: interrupted");
+        std::process::exit(130);
+    }
+}
@@ -0,0 +1,172 @@
+/*
+ * Output to a file through a memory map.
+ *
+ * The file is sized up front to hold exactly the final output, and every
+ * block is written straight to its own byte offset as soon as it is done.
+ * Unlike the streaming writer no block ever waits for the ones before it,
+ * and the digits live in the page cache rather than the heap.
+ */
+
+use memmap2::MmapMut;
+use rayon::prelude::*;
+use std::fs::File;
+use std::io::{self, Seek, SeekFrom, Write};
+use std::mem;
+use std::sync::atomic::Ordering;
+
+use crate::checkpoint::Checkpoint;
+use crate::{frame, Args, Tally};
+
+/* blocks handed to the thread pool at a time; the per-block slices of one
+ * window are the only memory that grows with the window */
+const WINDOW: usize = 1 << 12;
+
+/* byte offset of digit i within the digits, with a space after every
+ * `size` digits; for i == digits this is the length of the digits */
+fn offset(i: usize, size: usize) -> usize {
+    if i == 0 {
+        0
+    } else {
+        i + (i - 1) / size
+    }
+}
+
+/* write digits `first..end` of block `first / 9` from calc to `out`,
+ * preceded by the group separator wherever one falls between them */
+fn write_block(first: usize, end: usize, size: usize, calc: fn(i32) -> u64, out: &mut [u8]) {
+    let block = calc(first as i32 + 1);
+    let mut out = out.iter_mut();
+    for i in first..end {
+        if i > 0 && i % size == 0 {
+            *out.next().unwrap() = b' ';
+        }
+        let digit = block / 10u64.pow(8 - (i - first) as u32) % 10;
+        *out.next().unwrap() = b'0' + digit as u8;
+    }
+}
+
+pub(crate) fn write_pi(file: &File, digits: u32, args: &Args, tally: &Tally) -> io::Result<()> {
+    let (head, tail) = frame(digits, digits, args);
+    let checkpoint = args
+        .resume
+        .as_ref()
+        .map(|path| Checkpoint::new(path, digits, args));
+    let size = args.group.map_or(usize::MAX, |n| n as usize);
+    let calc = if args.exact {
+        parpi::calc_digits_exact
+    } else {
+        parpi::calc_digits
+    };
+    let digits = digits as usize;
+    let body_len = offset(digits, size);
+    let len = (head.len() + body_len + tail.len()) as u64;
+
+    let num_blocks = digits.div_ceil(9);
+    /* blocks already in a file of the right size need not be written again */
+    let done = match &checkpoint {
+        Some(c) if file.metadata()?.len() == len => c.load(num_blocks),
+        _ => 0,
+    };
+    file.set_len(len)?;
+
+    /* the map is only unsound if another process truncates the file while
+     * it is being written, which would be a bug in that process */
+    let mut map = unsafe { MmapMut::map_mut(file)? };
+    let (h, rest) = map.split_at_mut(head.len());
+    let (mut body, t) = rest.split_at_mut(body_len);
+    h.copy_from_slice(head.as_bytes());
+    t.copy_from_slice(tail.as_bytes());
+
+    let skipped = offset((9 * done).min(digits), size);
+    body = &mut body[skipped..];
+    tally
+        .written
+        .fetch_add((9 * done).min(digits), Ordering::Relaxed);
+
+    /* the blocks in place, all of them unless Ctrl-C stops the run */
+    let mut end_block = num_blocks;
+    let mut chunks = Vec::with_capacity(WINDOW.min(num_blocks));
+    for start in (done..num_blocks).step_by(WINDOW) {
+        for b in start..num_blocks.min(start + WINDOW) {
+            let end = (9 * b + 9).min(digits);
+            let len = offset(end, size) - offset(9 * b, size);
+            let (chunk, rest) = mem::take(&mut body).split_at_mut(len);
+            chunks.push((b, chunk));
+            body = rest;
+        }
+        /* rayon splits the window as it sees fit unless --chunk-size
+         * fixes the blocks per task */
+        let (min, max) = match args.chunk_size {
+            Some(n) => (n as usize, n as usize),
+            None => (1, usize::MAX),
+        };
+        let written: Vec<bool> = chunks
+            .par_drain(..)
+            .with_min_len(min)
+            .with_max_len(max)
+            .map(|(b, chunk)| {
+                /* after Ctrl-C, blocks not started yet are left out */
+                if crate::interrupted() {
+                    return false;
+                }
+                let end = (9 * b + 9).min(digits);
+                write_block(9 * b, end, size, calc, chunk);
+                tally.written.fetch_add(end - 9 * b, Ordering::Relaxed);
+                true
+            })
+            .collect();
+        /* the blocks of the window in place, up to the first left out */
+        let window_end = start + written.iter().take_while(|&&w| w).count();
+        /* the map shares the page cache, so a killed run leaves these
+         * blocks in the file anyway; the sync covers a crash of the whole
+         * system, through the file since the map itself is still borrowed */
+        if let Some(c) = &checkpoint {
+            file.sync_data()?;
+            c.save(window_end)?;
+        }
+        if crate::interrupted() {
+            end_block = window_end;
+            break;
+        }
+    }
+    let done_digits = (9 * end_block).min(digits);
+
+    /* the blocks were written out of order, so they are compared once
+     * they are all in place, a window at a time */
+    if args.verify || args.check.is_some() {
+        let mut start = 0;
+        let mut digits = Vec::with_capacity(9 * WINDOW);
+        let body = &map[head.len()..head.len() + offset(done_digits, size)];
+        for chunk in body.chunks(9 * WINDOW) {
+            digits.clear();
+            digits.extend(chunk.iter().filter(|&&c| c != b' '));
+            tally.compare(start, &digits)?;
+            start += digits.len();
+        }
+    }
+    map.flush()?;
+
+    /* an interrupted run that can be resumed keeps the file at its full
+     * size, which is how the next run knows the blocks in it; otherwise
+     * the file is cut down to the digits done and ends as if that many
+     * had been asked for */
+    if end_block == num_blocks || checkpoint.is_some() {
+        return Ok(());
+    }
+    drop(map);
+    let (done_head, tail) = frame(digits as u32, done_digits as u32, args);
+    /* with no digits done the head loses its decimal point, and as there
+     * is no body behind it, it is simply written again */
+    let keep = if done_head == head {
+        head.len() + offset(done_digits, size)
+    } else {
+        0
+    };
+    file.set_len(keep as u64)?;
+    let mut file = file;
+    file.seek(SeekFrom::End(0))?;
+    if done_head != head {
+        file.write_all(done_head.as_bytes())?;
+    }
+    file.write_all(tail.as_bytes())
+}
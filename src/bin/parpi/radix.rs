@@ -0,0 +1,54 @@
+/*
+ * Output in bases other than 10.
+ *
+ * Base 16 comes straight from the BBP formula, one digit per evaluation,
+ * and base 2 spells out each hex digit as four bits. Other bases would need
+ * a conversion of the decimal digits and are not supported yet.
+ */
+
+use rayon::prelude::*;
+use std::io::{self, Write};
+
+/* digits computed on the pool at a time */
+const WINDOW: u32 = 1 << 10;
+
+/* the bases --base accepts */
+pub(crate) const SUPPORTED: [u32; 3] = [2, 10, 16];
+
+/* the text in front of `digits` digits in `base`, as pi_prefix for base 10 */
+pub(crate) fn prefix(digits: u32, base: u32) -> String {
+    let integer = if base == 2 { "11" } else { "3" };
+    if digits == 0 {
+        integer.to_string()
+    } else {
+        format!("{}.", integer)
+    }
+}
+
+/* write exactly `digits` digits after the point in base 2 or 16 */
+pub(crate) fn write_digits_to<W: Write>(w: &mut W, digits: u32, base: u32) -> io::Result<()> {
+    let bits = if base == 2 { 4 } else { 1 };
+    let hex_digits = digits.div_ceil(bits);
+    let mut remaining = digits as usize;
+    for start in (0..hex_digits).step_by(WINDOW as usize) {
+        let end = hex_digits.min(start + WINDOW);
+        let hex: Vec<u8> = (start..end)
+            .into_par_iter()
+            .with_max_len(1)
+            .map(parpi::bbp::hex_digit_at)
+            .collect();
+        let text: Vec<u8> = if base == 2 {
+            hex.iter()
+                .flat_map(|&d| (0..4).rev().map(move |i| b'0' + (d >> i & 1)))
+                .collect()
+        } else {
+            hex.iter()
+                .map(|&d| b"0123456789ABCDEF"[d as usize])
+                .collect()
+        };
+        let n = remaining.min(text.len());
+        w.write_all(&text[..n])?;
+        remaining -= n;
+    }
+    Ok(())
+}
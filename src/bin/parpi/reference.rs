@@ -0,0 +1,80 @@
+/*
+ * Digits to compare the output against, either the built-in reference for
+ * --verify or a file given to --check.
+ *
+ * The file is read as the output is written, so a reference of any length
+ * is compared without holding it in memory. It holds the digits after the
+ * decimal point, which may be preceded by the leading 3 or "3." and may
+ * have whitespace anywhere, such as line breaks. Since the fractional
+ * digits of pi start with 1, a leading 3 is always the integer part.
+ */
+
+use std::fs::File;
+use std::io::{self, BufReader, Bytes, Read};
+use std::path::Path;
+
+pub(crate) struct Reference {
+    bytes: Bytes<BufReader<Box<dyn Read + Send>>>,
+    name: String,
+    started: bool,
+}
+
+impl Reference {
+    pub(crate) fn builtin() -> Self {
+        Reference::new(Box::new(parpi::REFERENCE_DIGITS.as_bytes()), "reference")
+    }
+
+    pub(crate) fn open(path: &Path) -> io::Result<Self> {
+        let f = File::open(path)?;
+        Ok(Reference::new(Box::new(f), &path.display().to_string()))
+    }
+
+    fn new(r: Box<dyn Read + Send>, name: &str) -> Self {
+        Reference {
+            bytes: BufReader::new(r).bytes(),
+            name: name.to_string(),
+            started: false,
+        }
+    }
+
+    /* the next byte that is not whitespace */
+    fn next_byte(&mut self) -> Option<io::Result<u8>> {
+        loop {
+            match self.bytes.next()? {
+                Ok(c) if c.is_ascii_whitespace() => {}
+                Ok(c) => return Some(Ok(c)),
+                Err(e) => return Some(Err(self.error(e.kind(), &e.to_string()))),
+            }
+        }
+    }
+
+    fn error(&self, kind: io::ErrorKind, msg: &str) -> io::Error {
+        io::Error::new(kind, format!("{}: {}", self.name, msg))
+    }
+}
+
+impl Iterator for Reference {
+    type Item = io::Result<u8>;
+
+    /* the next digit after the decimal point */
+    fn next(&mut self) -> Option<io::Result<u8>> {
+        let mut c = self.next_byte()?;
+        if !self.started {
+            self.started = true;
+            if let Ok(b'3') = c {
+                c = self.next_byte()?;
+                if let Ok(b'.') = c {
+                    c = self.next_byte()?;
+                }
+            }
+        }
+        Some(c.and_then(|c| {
+            if c.is_ascii_digit() {
+                Ok(c)
+            } else {
+                let msg = format!("unexpected {:?} among the digits", c as char);
+                Err(self.error(io::ErrorKind::InvalidData, &msg))
+            }
+        }))
+    }
+}
@@ -0,0 +1,86 @@
+/*
+ * An interactive prompt for digits at given positions.
+ *
+ * Each line is a zero-based position after the decimal point, such as
+ * 1000, or an inclusive range such as 1000-1010, in the same forms as the
+ * digit count. The reply is the digits there, or `error: ...`. Every block
+ * computed is kept for the rest of the session, so looking around one
+ * position again costs nothing.
+ */
+
+use parpi::{ParpiError, PiComputer};
+use std::collections::HashMap;
+use std::io::{self, BufRead, IsTerminal, Write};
+
+pub(crate) fn repl(pool: &rayon::ThreadPool, computer: &PiComputer) -> io::Result<()> {
+    let interactive = io::stdin().is_terminal();
+    let mut blocks = HashMap::new();
+    let mut stdout = io::stdout().lock();
+    let mut lines = io::stdin().lock().lines();
+    loop {
+        if interactive {
+            write!(stdout, "> ")?;
+            stdout.flush()?;
+        }
+        let Some(line) = lines.next().transpose()? else {
+            break;
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        match parse_query(line) {
+            Ok((start, end)) => {
+                pool.install(|| fill_cache(&mut blocks, computer, start, end));
+                for i in start..=end {
+                    let b = blocks[&(i / 9)];
+                    let digit = b / 10u32.pow(8 - i % 9) % 10;
+                    write!(stdout, "{}", digit)?;
+                }
+                writeln!(stdout)?;
+            }
+            Err(e) => writeln!(stdout, "error: {}", e)?,
+        }
+        stdout.flush()?;
+    }
+    /* leave the shell prompt on a line of its own */
+    if interactive {
+        writeln!(stdout)?;
+    }
+    Ok(())
+}
+
+/* parse `POS` or `FIRST-LAST` into an inclusive range of positions */
+fn parse_query(line: &str) -> Result<(u32, u32), ParpiError> {
+    let (start, end) = match line.split_once('-') {
+        Some((first, last)) => (first.trim(), last.trim()),
+        None => (line, line),
+    };
+    let start = parpi::parse_count(start)?;
+    let end = parpi::parse_count(end)?;
+    if start > end {
+        return Err(ParpiError::InvalidCount(line.to_string()));
+    }
+    /* the digit at `end` is the last of end + 1 digits */
+    parpi::check_count(end + 1).map_err(|_| ParpiError::CountTooLarge(line.to_string()))?;
+    Ok((start, end))
+}
+
+/* compute the blocks covering positions start..=end that are not cached */
+fn fill_cache(blocks: &mut HashMap<u32, u32>, computer: &PiComputer, start: u32, end: u32) {
+    let mut b = start / 9;
+    while b <= end / 9 {
+        if blocks.contains_key(&b) {
+            b += 1;
+            continue;
+        }
+        let run_end = (b..=end / 9)
+            .find(|n| blocks.contains_key(n))
+            .unwrap_or(end / 9 + 1);
+        let values = computer
+            .compute_range(b as i32, run_end as i32)
+            .expect("parse_query checks the positions");
+        blocks.extend((b..run_end).zip(values));
+        b = run_end;
+    }
+}
@@ -0,0 +1,108 @@
+/*
+ * A line-based TCP server for digit ranges.
+ *
+ * Each request is a line `START COUNT`, where START is the zero-based
+ * position after the decimal point and both numbers take the same forms
+ * as the digit count, such as 10K. The reply is a line holding exactly
+ * COUNT digits starting at START, or `error: ...` if the request is
+ * invalid. A connection may send any number of requests. Blocks are
+ * independent, so a range deep into the expansion costs only the blocks
+ * it covers.
+ *
+ * Every connection gets its own thread, and all of them compute their
+ * blocks on the one shared thread pool.
+ */
+
+use parpi::{ParpiError, PiComputer};
+use rayon::ThreadPool;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::thread;
+
+/* blocks computed at a time for one reply */
+const WINDOW: u32 = 64;
+
+pub(crate) fn serve(addr: &str, pool: ThreadPool, computer: PiComputer) -> io::Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    eprintln!("parpi: listening on {}", listener.local_addr()?);
+    let pool = Arc::new(pool);
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("parpi: {}", e);
+                continue;
+            }
+        };
+        let pool = Arc::clone(&pool);
+        let computer = computer.clone();
+        thread::spawn(move || {
+            let peer = stream.peer_addr();
+            if let Err(e) = handle(stream, &pool, &computer) {
+                match peer {
+                    Ok(peer) => eprintln!("parpi: {}: {}", peer, e),
+                    Err(_) => eprintln!("parpi: {}", e),
+                }
+            }
+        });
+    }
+    Ok(())
+}
+
+fn handle(stream: TcpStream, pool: &ThreadPool, computer: &PiComputer) -> io::Result<()> {
+    let reader = BufReader::new(stream.try_clone()?);
+    let mut w = BufWriter::new(stream);
+    for line in reader.lines() {
+        match parse_request(&line?) {
+            Ok((start, count)) => {
+                pool.install(|| write_range(&mut w, computer, start, count))?;
+                w.write_all(b"\n")?;
+            }
+            Err(e) => writeln!(w, "error: {}", e)?,
+        }
+        w.flush()?;
+    }
+    Ok(())
+}
+
+/* parse `START COUNT`, checking that the whole range can be computed */
+fn parse_request(line: &str) -> Result<(u32, u32), ParpiError> {
+    let mut fields = line.split_whitespace();
+    let (start, count) = match (fields.next(), fields.next(), fields.next()) {
+        (Some(start), Some(count), None) => (start, count),
+        _ => return Err(ParpiError::InvalidCount(line.to_string())),
+    };
+    let start = parpi::parse_count(start)?;
+    let count = parpi::parse_count(count)?;
+    let end = start
+        .checked_add(count)
+        .ok_or_else(|| ParpiError::CountTooLarge(line.to_string()))?;
+    parpi::check_count(end)?;
+    Ok((start, count))
+}
+
+/* write the digits at positions start..start + count */
+fn write_range<W: Write>(
+    w: &mut W,
+    computer: &PiComputer,
+    start: u32,
+    count: u32,
+) -> io::Result<()> {
+    let end = start + count;
+    let mut block = start / 9;
+    while 9 * block < end {
+        let window_end = (block + WINDOW).min(end.div_ceil(9));
+        let blocks = computer
+            .compute_range(block as i32, window_end as i32)
+            .expect("parse_request checks the range");
+        for (b, value) in (block..window_end).zip(blocks) {
+            let digits = format!("{:09}", value);
+            let first = (9 * b).max(start) - 9 * b;
+            let last = (9 * b + 9).min(end) - 9 * b;
+            w.write_all(&digits.as_bytes()[first as usize..last as usize])?;
+        }
+        block = window_end;
+    }
+    Ok(())
+}
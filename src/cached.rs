@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::{block_fn, MAX_DIGITS};
+
+/// Random access to the digits of pi at any index up to
+/// [`MAX_DIGITS`](crate::MAX_DIGITS), keeping the most recently used
+/// nine-digit blocks so that queries near the same position compute each
+/// block only once.
+///
+/// Unlike [`ComputedPi`](crate::ComputedPi), which keeps every block it has
+/// computed, at most `capacity` blocks are held: once the cache is full the
+/// least recently used block makes room for the next one. Digits are handed
+/// out by value, since a block may be evicted at any time.
+///
+/// ```
+/// let pi = parpi::CachedPi::with_capacity(16);
+/// assert_eq!(pi.digit_at(0), 1);
+/// assert!(pi.contains(8));
+/// ```
+#[derive(Debug)]
+pub struct CachedPi {
+    capacity: usize,
+    cache: Mutex<Blocks>,
+}
+
+/* the cached blocks by index, each with the tick of its last use */
+#[derive(Debug, Default)]
+struct Blocks {
+    blocks: HashMap<u32, (u32, u64)>,
+    tick: u64,
+}
+
+impl CachedPi {
+    /// Keep up to `capacity` blocks of nine digits. With a capacity of 0
+    /// every lookup computes its block afresh.
+    pub fn with_capacity(capacity: usize) -> Self {
+        CachedPi {
+            capacity,
+            cache: Mutex::new(Blocks::default()),
+        }
+    }
+
+    /// Return the digit at `index`, where index 0 is the first digit after
+    /// the decimal point, as [`pi_digit_at`](crate::pi_digit_at) does.
+    ///
+    /// Panics unless `index < MAX_DIGITS`, before the cache is touched.
+    pub fn digit_at(&self, index: u32) -> u8 {
+        assert!(index < MAX_DIGITS, "index {} lies beyond MAX_DIGITS", index);
+        let b = self.block(index / 9);
+        ((b / 10u32.pow(8 - index % 9)) % 10) as u8
+    }
+
+    /// Return true if the block holding the digit at `index` is cached.
+    pub fn contains(&self, index: u32) -> bool {
+        self.cache.lock().unwrap().blocks.contains_key(&(index / 9))
+    }
+
+    /// The number of blocks currently cached.
+    pub fn len(&self) -> usize {
+        self.cache.lock().unwrap().blocks.len()
+    }
+
+    /// Return true if no block is cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /* look up block i, computing it without holding the lock on a miss */
+    fn block(&self, i: u32) -> u32 {
+        {
+            let mut cache = self.cache.lock().unwrap();
+            cache.tick += 1;
+            let tick = cache.tick;
+            if let Some((b, used)) = cache.blocks.get_mut(&i) {
+                *used = tick;
+                return *b;
+            }
+        }
+        let b = block_fn(1)(i);
+        if self.capacity > 0 {
+            let mut cache = self.cache.lock().unwrap();
+            /* evicting takes a pass over the cache, which is cheap next to
+             * computing the block that missed */
+            if cache.blocks.len() >= self.capacity && !cache.blocks.contains_key(&i) {
+                let oldest = cache.blocks.iter().min_by_key(|(_, &(_, used))| used);
+                let oldest = *oldest.expect("a full cache is not empty").0;
+                cache.blocks.remove(&oldest);
+            }
+            let tick = cache.tick;
+            cache.blocks.insert(i, (b, tick));
+        }
+        b
+    }
+}
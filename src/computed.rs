@@ -0,0 +1,82 @@
+use std::ops::Index;
+use std::sync::OnceLock;
+
+use crate::{block_fn, check_count, write_block, ParpiError};
+
+/// Random access to the digits of pi after the decimal point, computing and
+/// caching each nine-digit block the first time one of its digits is read.
+///
+/// `pi[i]` is the digit at index `i`, where index 0 is the first digit after
+/// the decimal point, as in [`pi_digit_at`](crate::pi_digit_at). Indexing
+/// past [`len`](ComputedPi::len) panics like indexing a slice.
+///
+/// ```
+/// let pi = parpi::ComputedPi::with_digits(100)?;
+/// assert_eq!([pi[0], pi[1], pi[2]], [1, 4, 1]);
+/// # Ok::<(), parpi::ParpiError>(())
+/// ```
+///
+/// The digits are only as accurate as the `f64` accumulation in
+/// [`calc_digits`](crate::calc_digits). Far out in the expansion a block can
+/// come out wrong without any error being reported, so digits at large
+/// indices should not be trusted without an independent check.
+#[derive(Debug)]
+pub struct ComputedPi {
+    digits: u32,
+    blocks: Vec<OnceLock<[u8; 9]>>,
+}
+
+impl ComputedPi {
+    /// Give access to `digits` digits after the decimal point, none of which
+    /// are computed yet. Fails if `digits` is beyond
+    /// [`MAX_DIGITS`](crate::MAX_DIGITS).
+    pub fn with_digits(digits: u32) -> Result<Self, ParpiError> {
+        check_count(digits)?;
+        let blocks = (0..digits.div_ceil(9)).map(|_| OnceLock::new()).collect();
+        Ok(ComputedPi { digits, blocks })
+    }
+
+    /// The number of digits that can be indexed.
+    pub fn len(&self) -> usize {
+        self.digits as usize
+    }
+
+    /// Return true if there are no digits to index.
+    pub fn is_empty(&self) -> bool {
+        self.digits == 0
+    }
+
+    /// Return the digit at `index`, or `None` if it is past the end.
+    pub fn get(&self, index: usize) -> Option<u8> {
+        if index < self.len() {
+            Some(*self.digit(index))
+        } else {
+            None
+        }
+    }
+
+    /* the digit at an index known to be in range, as stored in the cache */
+    fn digit(&self, index: usize) -> &u8 {
+        let i = index / 9;
+        let block = self.blocks[i].get_or_init(|| {
+            let mut digits = [0; 9];
+            write_block(block_fn(1)(i as u32), &mut digits);
+            digits.map(|c| c - b'0')
+        });
+        &block[index % 9]
+    }
+}
+
+impl Index<usize> for ComputedPi {
+    type Output = u8;
+
+    fn index(&self, index: usize) -> &u8 {
+        assert!(
+            index < self.len(),
+            "digit index {} out of range for {} digits",
+            index,
+            self.digits
+        );
+        self.digit(index)
+    }
+}
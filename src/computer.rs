@@ -0,0 +1,207 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::io::{self, Write};
+
+use crate::par::Tasks;
+use crate::{
+    block, block_fn, check_count, digit_string, pi_prefix, range_blocks, sum_digits, ParpiError,
+};
+
+/// Configured computation of pi, created through [`PiComputer::new`].
+///
+/// ```
+/// # use parpi::PiComputer;
+/// let pi = PiComputer::new().digits(10).threads(2).prefix(false).build();
+/// assert_eq!(pi.compute()?, "1415926535");
+/// # Ok::<(), parpi::ParpiError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct PiComputer {
+    digits: u32,
+    #[cfg_attr(not(feature = "rayon"), allow(dead_code))]
+    threads: Option<usize>,
+    prefix: bool,
+    tasks: Tasks,
+    #[cfg(feature = "bignum")]
+    exact: bool,
+}
+
+/// Builder for [`PiComputer`].
+#[derive(Debug, Clone)]
+pub struct PiComputerBuilder {
+    digits: u32,
+    threads: Option<usize>,
+    prefix: bool,
+    chunk_size: u32,
+    window: u32,
+    #[cfg(feature = "bignum")]
+    exact: bool,
+}
+
+impl PiComputer {
+    /// Start building a computation of 800 digits on all cores, printed with
+    /// the leading `3.`.
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new() -> PiComputerBuilder {
+        PiComputerBuilder::default()
+    }
+
+    /* run f on a pool of the configured number of threads, or on the
+     * current one */
+    fn run<R: Send>(&self, f: impl FnOnce() -> R + Send) -> Result<R, ParpiError> {
+        #[cfg(feature = "rayon")]
+        if let Some(n) = self.threads {
+            return Ok(rayon::ThreadPoolBuilder::new()
+                .num_threads(n)
+                .build()
+                .map_err(|e| ParpiError::ThreadPool(e.to_string()))?
+                .install(f));
+        }
+        Ok(f())
+    }
+
+    /* the blocks to compute: `fast`, or the exact ones if asked for */
+    fn block_fn(&self, fast: fn(u32) -> u32) -> fn(u32) -> u32 {
+        #[cfg(feature = "bignum")]
+        if self.exact {
+            return crate::exact::block_exact;
+        }
+        fast
+    }
+
+    /// Compute the configured number of digits after the decimal point.
+    pub fn compute(&self) -> Result<String, ParpiError> {
+        check_count(self.digits)?;
+        let pi = self.run(|| digit_string(self.digits, self.tasks, self.block_fn(block)))?;
+        if self.prefix {
+            Ok(pi)
+        } else {
+            Ok(pi[pi_prefix(self.digits).len()..].to_string())
+        }
+    }
+
+    /// Stream the configured digits to `w` as
+    /// [`write_pi_to`](crate::write_pi_to) does, or as
+    /// [`write_digits_to`](crate::write_digits_to) does without the prefix.
+    ///
+    /// A digit count beyond [`MAX_DIGITS`](crate::MAX_DIGITS) fails with
+    /// [`io::ErrorKind::InvalidInput`] before anything is written.
+    #[cfg(feature = "std")]
+    pub fn write_to<W: Write + Send>(&self, w: &mut W) -> io::Result<()> {
+        check_count(self.digits).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        self.run(|| {
+            if self.prefix {
+                w.write_all(pi_prefix(self.digits).as_bytes())?;
+            }
+            crate::stream::write_digits(w, self.digits, self.tasks, self.block_fn(block))
+        })
+        .map_err(io::Error::other)?
+    }
+
+    /// Return the sum of the configured digits after the decimal point, as
+    /// [`digit_sum`](crate::digit_sum) does.
+    pub fn digit_sum(&self) -> Result<u64, ParpiError> {
+        check_count(self.digits)?;
+        let block_at = self.block_fn(block_fn(self.digits.div_ceil(9)));
+        self.run(|| sum_digits(self.digits, self.tasks, block_at))
+    }
+
+    /// Compute blocks `start_block..end_block` as
+    /// [`compute_range`](crate::compute_range) does, ignoring the configured
+    /// digit count and prefix.
+    ///
+    /// Blocks beyond [`MAX_DIGITS`](crate::MAX_DIGITS) fail with
+    /// [`ParpiError::CountTooLarge`].
+    pub fn compute_range(&self, start_block: i32, end_block: i32) -> Result<Vec<u32>, ParpiError> {
+        check_count((end_block.max(0) as u32).saturating_mul(9))?;
+        let blocks = end_block.saturating_sub(start_block).max(0) as u32;
+        let block_at = self.block_fn(block_fn(blocks));
+        self.run(|| range_blocks(start_block, end_block, self.tasks, block_at))
+    }
+}
+
+impl Default for PiComputerBuilder {
+    fn default() -> Self {
+        PiComputerBuilder {
+            digits: 800,
+            threads: None,
+            prefix: true,
+            chunk_size: 0,
+            window: 0,
+            #[cfg(feature = "bignum")]
+            exact: false,
+        }
+    }
+}
+
+impl PiComputerBuilder {
+    /// Number of digits after the decimal point.
+    pub fn digits(mut self, digits: u32) -> Self {
+        self.digits = digits;
+        self
+    }
+
+    /// Number of worker threads; defaults to one per core. Ignored when the
+    /// `rayon` feature is disabled.
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = Some(threads);
+        self
+    }
+
+    /// Whether the output starts with `3.`.
+    pub fn prefix(mut self, prefix: bool) -> Self {
+        self.prefix = prefix;
+        self
+    }
+
+    /// Number of consecutive nine-digit blocks each parallel task computes;
+    /// defaults to one, as does `0`.
+    ///
+    /// The cost of a block grows with its position, so larger chunks mean
+    /// fewer tasks but a worse balance between the threads: with `n` blocks
+    /// in chunks of `size`, at most `n / size` threads have any work.
+    /// Ignored when the `rayon` feature is disabled.
+    pub fn chunk_size(mut self, size: u32) -> Self {
+        self.chunk_size = size;
+        self
+    }
+
+    /// Most blocks [`write_to`](PiComputer::write_to) keeps computed or
+    /// waiting to be written at any time; defaults to four per thread, as
+    /// does `0`.
+    ///
+    /// Blocks finish out of order and wait in a buffer until every block
+    /// before them is written, so the window bounds that buffer. A small
+    /// window holds little memory but lets one slow block stall every
+    /// thread; a window is never smaller than one chunk of
+    /// [`chunk_size`](Self::chunk_size) blocks.
+    pub fn window(mut self, blocks: u32) -> Self {
+        self.window = blocks;
+        self
+    }
+
+    /// Whether the digits come from
+    /// [`calc_digits_exact`](crate::calc_digits_exact), which gets every
+    /// position up to [`MAX_DIGITS`](crate::MAX_DIGITS) right at a small cost
+    /// in speed, rather than from the `f64` sum; defaults to `false`.
+    #[cfg(feature = "bignum")]
+    pub fn exact(mut self, exact: bool) -> Self {
+        self.exact = exact;
+        self
+    }
+
+    pub fn build(self) -> PiComputer {
+        PiComputer {
+            digits: self.digits,
+            threads: self.threads,
+            prefix: self.prefix,
+            tasks: Tasks {
+                chunk: self.chunk_size,
+                window: self.window,
+            },
+            #[cfg(feature = "bignum")]
+            exact: self.exact,
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use core::fmt;
+
+use crate::par::Tasks;
+use crate::{block, pi_prefix, stream, write_block};
+
+/// Pi to a fixed number of digits, computed each time it is formatted.
+///
+/// Nothing is cached, so formatting the same value twice computes the digits
+/// twice. The blocks are streamed to the formatter as they are done, like
+/// [`write_pi_to`](crate::write_pi_to), rather than collected into a string
+/// first.
+///
+/// ```
+/// let pi = parpi::Pi::with_digits(10);
+/// assert_eq!(pi.to_string(), "3.1415926535");
+/// assert_eq!(format!("pi = {}", pi), "pi = 3.1415926535");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pi {
+    digits: u32,
+}
+
+impl Pi {
+    /// Pi with exactly `digits` digits after the decimal point.
+    pub fn with_digits(digits: u32) -> Self {
+        Pi { digits }
+    }
+
+    /// The number of digits after the decimal point.
+    pub fn digits(&self) -> u32 {
+        self.digits
+    }
+}
+
+impl fmt::Display for Pi {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(pi_prefix(self.digits))?;
+        let mut remaining = self.digits as usize;
+        stream::for_each_block(self.digits.div_ceil(9), Tasks::default(), block, |_, b| {
+            let mut buf = [0; 9];
+            let n = remaining.min(9);
+            remaining -= n;
+            write_block(b, &mut buf[..n]);
+            f.write_str(core::str::from_utf8(&buf[..n]).expect("digits are ASCII"))
+        })
+    }
+}
@@ -0,0 +1,45 @@
+use alloc::string::String;
+use core::fmt;
+
+/// Errors returned by the fallible parts of the library.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParpiError {
+    /// The digit count could not be parsed.
+    InvalidCount(String),
+    /// The digit count is larger than [`MAX_DIGITS`](crate::MAX_DIGITS).
+    CountTooLarge(String),
+    /// The worker thread pool could not be created.
+    ThreadPool(String),
+    /// Writing the digits failed.
+    Io(String),
+    /// The digit at this index after the decimal point does not match the
+    /// reference.
+    Mismatch(usize),
+}
+
+impl fmt::Display for ParpiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParpiError::InvalidCount(s) => write!(f, "invalid digit count '{}'", s),
+            ParpiError::CountTooLarge(s) => write!(
+                f,
+                "digit count {} is too large, at most {} digits are supported",
+                s,
+                crate::MAX_DIGITS
+            ),
+            ParpiError::ThreadPool(e) => write!(f, "failed to start worker threads: {}", e),
+            ParpiError::Io(e) => write!(f, "{}", e),
+            ParpiError::Mismatch(i) => write!(f, "digit {} does not match the reference", i),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParpiError {}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for ParpiError {
+    fn from(e: std::io::Error) -> Self {
+        ParpiError::Io(e.to_string())
+    }
+}
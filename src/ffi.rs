@@ -0,0 +1,45 @@
+/*
+ * C ABI for calling the computation from other languages, declared in
+ * include/parpi.h. Panics must not unwind into the caller, so every input
+ * that would make the Rust functions panic is rejected up front.
+ */
+
+use std::convert::TryFrom;
+use std::slice;
+
+use crate::{check_count, fill_digits, pi_digit_at, MAX_DIGITS};
+
+/// Fill `buf` with `len` ASCII digits of pi, `3` followed by the
+/// fractional digits without a decimal point, and return the number of
+/// bytes written.
+///
+/// The buffer is owned by the caller, who must make it valid for writes
+/// of `len` bytes; nothing is retained after the call returns and no NUL
+/// terminator is written. Returns 0 without touching the buffer if `buf`
+/// is null or `len` is 0 or more than one plus [`MAX_DIGITS`].
+///
+/// # Safety
+///
+/// `buf` must be null or point to `len` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn parpi_fill(buf: *mut u8, len: usize) -> usize {
+    let digits = len.checked_sub(1).and_then(|d| u32::try_from(d).ok());
+    match digits {
+        Some(d) if !buf.is_null() && check_count(d).is_ok() => {
+            fill_digits(slice::from_raw_parts_mut(buf, len))
+        }
+        _ => 0,
+    }
+}
+
+/// Return the decimal digit of pi at `index`, where index 0 is the first
+/// digit after the decimal point, or 255 if `index` is not below
+/// [`MAX_DIGITS`].
+#[no_mangle]
+pub extern "C" fn parpi_digit_at(index: u32) -> u8 {
+    if index < MAX_DIGITS {
+        pi_digit_at(index)
+    } else {
+        255
+    }
+}
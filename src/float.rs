@@ -0,0 +1,36 @@
+/*
+ * The f64 functions used by the digit extraction. Without std they are not
+ * available as methods and come from libm instead, which computes the same
+ * results for fract and round; ln may differ in the last bit, which at most
+ * changes the number of terms summed by one.
+ */
+
+#[cfg(feature = "std")]
+pub(crate) fn ln(x: f64) -> f64 {
+    x.ln()
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn ln(x: f64) -> f64 {
+    libm::log(x)
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn fract(x: f64) -> f64 {
+    x.fract()
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn fract(x: f64) -> f64 {
+    x - libm::trunc(x)
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn round(x: f64) -> f64 {
+    x.round()
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn round(x: f64) -> f64 {
+    libm::round(x)
+}
@@ -0,0 +1,146 @@
+use crate::{block, MAX_DIGITS};
+
+/// Lazily computes the nine-digit blocks of pi after the decimal point.
+///
+/// Each item is the integer value of one block, so `141592653` is followed
+/// by `589793238`. Blocks are computed one at a time as the iterator is
+/// advanced. [`PiBlocks::new`] runs up to [`MAX_DIGITS`], while
+/// [`PiBlocks::with_digits`] stops after the blocks covering the given
+/// number of digits; either way the number of blocks left is known, so the
+/// iterator is an [`ExactSizeIterator`].
+///
+/// ```
+/// let blocks: Vec<u32> = parpi::PiBlocks::new().take(2).collect();
+/// assert_eq!(blocks, [141592653, 589793238]);
+/// assert_eq!(parpi::PiBlocks::with_digits(10).len(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PiBlocks {
+    index: u32,
+    end: u32,
+}
+
+impl PiBlocks {
+    /// Iterate over every block up to [`MAX_DIGITS`].
+    pub fn new() -> Self {
+        PiBlocks::with_digits(MAX_DIGITS)
+    }
+
+    /// Iterate over the blocks covering `digits` digits after the decimal
+    /// point. The last block is yielded whole, so it may extend past
+    /// `digits`.
+    ///
+    /// Panics if `digits` is larger than [`MAX_DIGITS`].
+    pub fn with_digits(digits: u32) -> Self {
+        assert!(digits <= MAX_DIGITS, "digit count beyond MAX_DIGITS");
+        PiBlocks {
+            index: 0,
+            end: digits.div_ceil(9),
+        }
+    }
+}
+
+impl Default for PiBlocks {
+    fn default() -> Self {
+        PiBlocks::new()
+    }
+}
+
+impl Iterator for PiBlocks {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        if self.index == self.end {
+            return None;
+        }
+        let b = block(self.index);
+        self.index += 1;
+        Some(b)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.end - self.index) as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for PiBlocks {}
+
+/// Lazily yields the decimal digits of pi one at a time, starting with the
+/// leading `3` and continuing with the fractional digits.
+///
+/// Every item is in `0..=9`. Leading zeros of a block are yielded as zero
+/// digits, exactly as they appear in the zero-padded decimal expansion.
+/// Like [`PiBlocks`] this is an [`ExactSizeIterator`], with
+/// [`PiDigitStream::with_digits`] yielding the `3` and exactly the given
+/// number of digits after it.
+///
+/// ```
+/// let digits: Vec<u8> = parpi::PiDigitStream::new().take(6).collect();
+/// assert_eq!(digits, [3, 1, 4, 1, 5, 9]);
+/// assert_eq!(parpi::PiDigitStream::with_digits(10).len(), 11);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PiDigitStream {
+    blocks: PiBlocks,
+    current: u32,
+    /* number of digits of `current` not yet yielded, or None before the 3 */
+    remaining: Option<u32>,
+    /* number of digits after the decimal point not yet yielded */
+    left: u32,
+}
+
+impl PiDigitStream {
+    /// Yield every digit up to [`MAX_DIGITS`].
+    pub fn new() -> Self {
+        PiDigitStream::with_digits(MAX_DIGITS)
+    }
+
+    /// Yield the `3` and then `digits` digits after the decimal point.
+    ///
+    /// Panics if `digits` is larger than [`MAX_DIGITS`].
+    pub fn with_digits(digits: u32) -> Self {
+        PiDigitStream {
+            blocks: PiBlocks::with_digits(digits),
+            current: 0,
+            remaining: None,
+            left: digits,
+        }
+    }
+}
+
+impl Default for PiDigitStream {
+    fn default() -> Self {
+        PiDigitStream::new()
+    }
+}
+
+impl Iterator for PiDigitStream {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        let remaining = match self.remaining {
+            None => {
+                self.remaining = Some(0);
+                return Some(3);
+            }
+            _ if self.left == 0 => return None,
+            Some(0) => {
+                self.current = self.blocks.next()?;
+                9
+            }
+            Some(r) => r,
+        };
+        let digit = (self.current / 10u32.pow(remaining - 1)) % 10;
+        self.remaining = Some(remaining - 1);
+        self.left -= 1;
+        Some(digit as u8)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.left as usize + self.remaining.is_none() as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for PiDigitStream {}
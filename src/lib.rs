@@ -0,0 +1,695 @@
+/*
+ * Computation of the n'th decimal digit of pi with very little memory.
+ * Written by Fabrice Bellard on February 26, 1997.
+ *
+ * We use a slightly modified version of the method described by Simon
+ * Plouffe in "On the Computation of the n'th decimal digit of various
+ * transcendental numbers" (November 1996). We have modified the algorithm
+ * to get a running time of O(n^2) instead of O(n^3log(n)^3).
+ *
+ * This program uses a variation of the formula found by Gosper in 1974 :
+ *
+ * pi = sum( (25*n-3)/(binomial(3*n,n)*2^(n-1)), n=0..infinity);
+ *
+ * This program uses mostly integer arithmetic. It may be slow on some
+ * hardwares where integer multiplications and divisons must be done by
+ * software. We have supposed that 'int' has a size of at least 32 bits. If
+ * your compiler supports 'long long' integers of 64 bits, you may use the
+ * integer version of 'mul_mod' (see HAS_LONG_LONG).
+ */
+
+/* Ported to Rust by Frank A. Stevenson 2021 */
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod bbp;
+#[cfg(feature = "std")]
+pub mod bcd;
+pub mod modmath;
+
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use modmath::{inv_mod, inv_mod2, max_power, mul_mod, pow_mod};
+
+macro_rules! divn {
+    ($t:expr, $a:expr, $v:expr, $vinc:expr, $kq:expr, $kqinc:expr) => {
+        $kq += $kqinc;
+        if $kq >= $a {
+            loop {
+                $kq -= $a;
+                if $kq < $a {
+                    break;
+                }
+            }
+            if $kq == 0 {
+                loop {
+                    $t /= $a;
+                    $v += $vinc;
+                    if ($t % $a) != 0 {
+                        break;
+                    }
+                }
+            }
+        }
+    };
+}
+
+/* enter a tracing span at the given level for the rest of the scope; without
+ * the tracing feature this expands to nothing */
+macro_rules! trace_span {
+    ($level:ident, $($span:tt)*) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::span!(tracing::Level::$level, $($span)*).entered();
+    };
+}
+
+/// Return the number of terms of the series that [`calc_digits`] sums for
+/// the digits at position `n`.
+///
+/// Every prime up to `3 * num_terms(n)` walks all of these terms, so the
+/// work for position `n` grows roughly with the square of this count.
+/// [`check_count`] rejects counts where `25 * num_terms(n)` would overflow.
+pub fn num_terms(n: i32) -> i32 {
+    /* term k is about k^1.5 / 13.5^k times a constant. The first
+     * (n + 20) log 10 / log 13.5 terms would leave out 1e-20 of 10^(n-1)
+     * but for the k^1.5, which 1.5 log(n + 20) / log 13.5 more terms make
+     * up for. Without them what was left out grew to 1e-13 around
+     * position 19000 and was the largest error */
+    let n = (n + 20) as f64;
+    ((n * float::ln(10.0) + 1.5 * float::ln(n)) / float::ln(13.5)) as i32
+}
+
+/* return the contribution of the prime a to calc_frac(n), where nl is
+ * num_terms(n), with the arithmetic modulo a^vmax done in T */
+fn prime_frac<T: ModInt>(a: i32, n: i32, nl: i32) -> f64 {
+    match prime_residue::<T>(a, n, nl) {
+        Some((s, av)) => s.to_f64() / av.to_f64(),
+        None => 0.0,
+    }
+}
+
+/* return the contribution of the prime a as the fraction s / av, with
+ * 0 <= s < av = a^vmax, or None if it adds nothing. The factors of each
+ * term and the exponent bookkeeping stay in i32 */
+pub(crate) fn prime_residue<T: ModInt>(a: i32, n: i32, nl: i32) -> Option<(T, T)> {
+    let mut num;
+    let mut t;
+    let mut t1;
+    let mut v;
+
+    let mut vmax = max_power(a, 3 * nl);
+    if a == 2 {
+        vmax += nl - n;
+    }
+    /* with vmax <= 0 the modulus a^vmax would be 1: such a prime adds
+     * nothing to the sum, and inv_mod2 never terminates modulo 1 */
+    if vmax <= 0 {
+        return None;
+    }
+    /* powers[k] = a^k, which turns the t * a^(vmax - v) step below into
+     * a single multiplication */
+    let mut powers = [T::from(1); 32];
+    for k in 1..=vmax as usize {
+        powers[k] = powers[k - 1] * T::from(a);
+    }
+    let av = powers[vmax as usize];
+
+    let mut s = T::from(0);
+    let mut den = T::from(1);
+    let mut kq1 = 0;
+    let mut kq2 = -1;
+    let mut kq3 = -3;
+    let mut kq4 = -2;
+
+    /* the series is scaled by 2^n (odd primes only) and 5^(n-1);
+     * both factors are applied once after the loop */
+    num = T::from(1);
+    if a == 2 {
+        v = -n;
+    } else {
+        v = 0;
+    }
+
+    for k in 1..nl + 1 {
+        let mut n1 = 2 * k;
+        divn!(n1, a, v, -1, kq1, 2);
+
+        let mut n2 = 2 * k - 1;
+        divn!(n2, a, v, -1, kq2, 2);
+
+        let mut d1 = 3 * (3 * k - 1);
+        divn!(d1, a, v, 1, kq3, 9);
+
+        let mut d2 = 3 * k - 2;
+        divn!(d2, a, v, 1, kq4, 3);
+        if a != 2 {
+            d2 *= 2;
+        } else {
+            v += 1;
+        }
+
+        let (n1, n2, d1, d2) = (T::from(n1), T::from(n2), T::from(d1), T::from(d2));
+
+        /* advancing the two products side by side in fixed-size lanes, or
+         * reducing them through an f64 reciprocal of av, both made the
+         * block at 10000 slower on x86-64, which has no vector remainder:
+         * 1.96s and 2.24s against 1.87s */
+        num = mul_mod(mul_mod(num, n1, av), n2, av);
+        den = mul_mod(mul_mod(den, d1, av), d2, av);
+
+        if v > 0 {
+            if a != 2 {
+                t = inv_mod2(den, av);
+            } else {
+                t = inv_mod(den, av);
+            }
+            t = mul_mod(t, num, av);
+            if v < vmax {
+                t = mul_mod(t, powers[(vmax - v) as usize], av);
+            }
+            t1 = T::from(25 * k - 3);
+            t = mul_mod(t, t1, av);
+            s = s + t;
+            if s >= av {
+                s = s - av;
+            }
+        }
+    }
+    if a == 2 {
+        t = pow_mod(T::from(5), n - 1, av);
+    } else {
+        t = mul_mod(T::from(2), pow_mod(T::from(10), n - 1, av), av);
+    }
+    s = mul_mod(s, t, av);
+    Some((s, av))
+}
+
+/*
+ * return the fractional part of 10^(n-1) * pi, i.e. 0.d(n)d(n+1)...
+ *
+ * The plain f64 sum is not what limits the accuracy. Adding each s / av
+ * exactly in 96-bit fixed point, or with Kahan summation, gave the same
+ * error against the known digits as this loop at positions 1000 to 19000;
+ * that error came from the series being cut short (see num_terms). With
+ * enough terms the error of this loop stayed below 1e-14 up to position
+ * 59000. Further out the rounding grows with the number of primes, which is
+ * what exact.rs does away with.
+ */
+fn calc_frac<T: ModInt>(n: i32) -> f64 {
+    let nl = num_terms(n);
+    let mut sum = 0.0;
+    /* the next prime to report progress at, every eighth of the way */
+    #[cfg(feature = "tracing")]
+    let mut milestone = 0;
+    for &a in primes::primes_up_to(3 * nl)
+        .iter()
+        .take_while(|&&a| a <= 3 * nl)
+    {
+        #[cfg(feature = "tracing")]
+        if a >= milestone {
+            tracing::debug!(n, prime = a, limit = 3 * nl, "summing primes");
+            milestone = a + (3 * nl / 8).max(1);
+        }
+        sum = float::fract(sum + prime_frac::<T>(a, n, nl));
+    }
+    sum
+}
+
+/* calc_frac with the primes split across threads; the partial sums are
+ * added in a different order, which may change the last bits of the
+ * result but not the digits to_block extracts from it */
+fn calc_frac_split(n: i32) -> f64 {
+    let nl = num_terms(n);
+    let primes = primes::primes_up_to(3 * nl);
+    let end = primes.partition_point(|&a| a <= 3 * nl);
+    par::sum_fracs(&primes[..end], |a| prime_frac::<i32>(a, n, nl))
+}
+
+/* values within this distance of a digit boundary are ambiguous */
+const GUARD: f64 = 1e-4;
+
+/*
+ * Turn the fraction 0.d(n)d(n+1)... into the block d(n)..d(n+8).
+ *
+ * calc_frac is accurate to about 1e-14, so truncating frac * 1e9 is exact
+ * unless the block is followed by a long run of 0s or 9s and the error can
+ * push the value across a digit boundary. Such values are rounded to the
+ * nearest boundary instead and then corrected with the fraction at the next
+ * position: if the digits after the block start with 9s the true value lies
+ * just below the boundary. Rounding up to 1_000_000_000 means the true
+ * fraction wrapped past 1, so it becomes 0; no carry into the previous block
+ * is needed since that block is computed on its own and already has the
+ * right digits.
+ */
+fn to_block(frac: f64, next_frac: impl FnOnce() -> f64) -> u64 {
+    let x = frac * 1e9;
+    let nearest = float::round(x);
+    if (x - nearest).abs() >= GUARD {
+        return x as u64;
+    }
+    let below = next_frac() >= 0.5;
+    (nearest as u64 + 1_000_000_000 - below as u64) % 1_000_000_000
+}
+
+/// Compute the nine decimal digits of pi starting at position `n`, where
+/// position 1 is the first digit after the decimal point.
+///
+/// Panics unless `1 <= n` and the nine digits end no later than
+/// [`MAX_DIGITS`], i.e. `n <= MAX_DIGITS - 8`.
+pub fn calc_digits(n: i32) -> u64 {
+    calc_digits_with::<i32>(n)
+}
+
+/// [`calc_digits`] with the modular arithmetic done in `T` instead of
+/// `i32`, e.g. `calc_digits_with::<i64>(n)`.
+///
+/// Every width gives the same digits; `i64` is slower, but its moduli are
+/// not limited to `2^30`. The positions that can be computed accurately are
+/// still bounded by the `f64` sum, see [`max_accurate_digits`], unlike with
+/// `calc_digits_exact` behind the `bignum` feature.
+pub fn calc_digits_with<T: ModInt>(n: i32) -> u64 {
+    check_position(n);
+    to_block(calc_frac::<T>(n), || calc_frac::<T>(n + 9))
+}
+
+/* panic unless the digits at positions n..n + 9 lie within MAX_DIGITS.
+ * check_count of the last of them also covers the look-ahead at n + 9;
+ * past it 3 * num_terms(n) overflows, and below 1 pow_mod never ends */
+pub(crate) fn check_position(n: i32) {
+    assert!(n >= 1, "position {} is before the first digit", n);
+    assert!(
+        check_count(n as u32 + 8).is_ok(),
+        "position {} lies beyond MAX_DIGITS",
+        n
+    );
+}
+
+/* calc_digits with each position's prime sum split across threads */
+fn calc_digits_split(n: i32) -> u64 {
+    check_position(n);
+    to_block(calc_frac_split(n), || calc_frac_split(n + 9))
+}
+
+/// Compute nine-digit block number `index`, i.e. the digits at positions
+/// `9 * index + 1 ..= 9 * index + 9` after the decimal point.
+///
+/// Panics if the block lies beyond [`MAX_DIGITS`], i.e. unless
+/// `index < MAX_DIGITS / 9`.
+pub(crate) fn block(index: u32) -> u32 {
+    trace_span!(DEBUG, "block", index);
+    calc_digits(block_position(index)) as u32
+}
+
+/* like block, but for use when there are too few blocks to occupy every
+ * thread: the primes of the block are spread across the threads instead */
+fn block_split(index: u32) -> u32 {
+    trace_span!(DEBUG, "block", index, split = true);
+    calc_digits_split(block_position(index)) as u32
+}
+
+/* return the position of the first digit of block `index`. MAX_DIGITS is
+ * a multiple of 9, so the last block it allows ends exactly on it */
+pub(crate) fn block_position(index: u32) -> i32 {
+    assert!(
+        index < MAX_DIGITS / 9,
+        "block {} lies beyond MAX_DIGITS",
+        index
+    );
+    (9 * index + 1) as i32
+}
+
+/* pick how to compute `num_blocks` blocks: one block per task normally,
+ * or one prime per task when the blocks alone would leave threads idle */
+pub(crate) fn block_fn(num_blocks: u32) -> fn(u32) -> u32 {
+    if (num_blocks as usize) < par::num_threads() {
+        block_split
+    } else {
+        block
+    }
+}
+
+/* sieve the primes needed by blocks below end_block up front, so that the
+ * workers share a single list instead of growing it as they go; panics if
+ * the blocks go beyond MAX_DIGITS, before any work is started */
+pub(crate) fn prepare_blocks(end_block: u32) {
+    assert!(
+        end_block <= MAX_DIGITS / 9,
+        "{} blocks go beyond MAX_DIGITS",
+        end_block
+    );
+    /* the last block looks ahead at the position right after it */
+    primes::primes_up_to(3 * num_terms((9 * end_block + 1) as i32));
+}
+
+#[cfg(feature = "std")]
+mod cached;
+#[cfg(feature = "std")]
+mod computed;
+mod computer;
+mod display;
+mod error;
+#[cfg(feature = "bignum")]
+mod exact;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod float;
+mod iter;
+#[cfg(feature = "node")]
+pub mod node;
+mod par;
+mod primes;
+#[cfg(feature = "python")]
+mod python;
+mod reference;
+#[cfg(feature = "serde")]
+mod result;
+mod stream;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(feature = "std")]
+pub use cached::CachedPi;
+#[cfg(feature = "std")]
+pub use computed::ComputedPi;
+pub use computer::{PiComputer, PiComputerBuilder};
+pub use display::Pi;
+pub use error::ParpiError;
+#[cfg(feature = "bignum")]
+pub use exact::calc_digits_exact;
+pub use iter::{PiBlocks, PiDigitStream};
+pub use modmath::ModInt;
+pub use reference::REFERENCE_DIGITS;
+#[cfg(feature = "serde")]
+pub use result::PiResult;
+#[cfg(feature = "async")]
+pub use stream::pi_stream;
+#[cfg(feature = "std")]
+pub use stream::{write_digits_to, write_pi_to};
+
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+use par::Tasks;
+
+/// Largest digit count accepted by [`check_count`]. Beyond this the
+/// `25 * k` term in [`calc_digits`] no longer fits in an `i32`.
+pub const MAX_DIGITS: u32 = 97_094_898;
+
+/// Check that `digits` digits after the decimal point can be computed
+/// without overflowing the `i32` arithmetic in [`calc_digits`], returning
+/// [`ParpiError::CountTooLarge`] otherwise.
+pub fn check_count(digits: u32) -> Result<u32, ParpiError> {
+    let too_large = || ParpiError::CountTooLarge(digits.to_string());
+
+    /* the last block looks ahead at the position right after it */
+    let n = digits
+        .div_ceil(9)
+        .checked_mul(9)
+        .and_then(|n| n.checked_add(1))
+        .and_then(|n| i32::try_from(n).ok())
+        .filter(|n| n.checked_add(20).is_some())
+        .ok_or_else(too_large)?;
+    let nl = num_terms(n);
+
+    /* 25 * k - 3 is the largest product of the term index, and the moduli
+     * must stay below 2^30 for inv_mod2 */
+    match nl.checked_mul(25) {
+        Some(_) if nl.checked_mul(3).is_some_and(|m| m < 1 << 30) => Ok(digits),
+        _ => Err(too_large()),
+    }
+}
+
+/// Return a conservative estimate of how many digits after the decimal point
+/// the `f64` accumulation in [`calc_digits`] gets right.
+///
+/// The rounding error of the sum over primes grows with the position. The
+/// last digits of a block close to a digit boundary are only settled
+/// correctly while the error stays below a margin of `1e-13`; beyond that
+/// such a block may come out wrong, and nothing reports it. Compared
+/// against known digits the error stayed below `1e-14` up to position
+/// 59000.
+///
+/// The error depends only on the position of a block, not on how many
+/// digits are requested: every block is computed on its own, so computing
+/// extra digits and throwing them away does not make the ones kept any
+/// more reliable.
+///
+/// With the `bignum` feature, `calc_digits_exact` and
+/// `PiComputerBuilder::exact` sum in fixed point instead and have no such
+/// ceiling below [`MAX_DIGITS`].
+pub fn max_accurate_digits() -> u32 {
+    50_000
+}
+
+/// Parse a digit count as given on the command line.
+///
+/// Besides plain decimal numbers this accepts `_` between digits, as in
+/// `1_000_000`, and a single `K` or `M` suffix for thousands and millions,
+/// as in `10K` or `2M`. Anything else, such as `1MB`, is rejected.
+pub fn parse_count(s: &str) -> Result<u32, ParpiError> {
+    let invalid = || ParpiError::InvalidCount(s.to_string());
+    let too_large = || ParpiError::CountTooLarge(s.to_string());
+
+    let (number, scale) = match s.char_indices().last() {
+        Some((i, 'k')) | Some((i, 'K')) => (&s[..i], 1_000),
+        Some((i, 'm')) | Some((i, 'M')) => (&s[..i], 1_000_000),
+        _ => (s, 1),
+    };
+    if number.starts_with('_') || number.ends_with('_') {
+        return Err(invalid());
+    }
+    let digits: String = number.chars().filter(|&c| c != '_').collect();
+    if digits.is_empty() || !digits.bytes().all(|c| c.is_ascii_digit()) {
+        return Err(invalid());
+    }
+
+    let count = digits
+        .parse::<u32>()
+        .ok()
+        .and_then(|n| n.checked_mul(scale))
+        .ok_or_else(too_large)?;
+    check_count(count).map_err(|_| too_large())
+}
+
+/// Compute blocks `start_block..end_block` in parallel and return their
+/// values in order.
+///
+/// Blocks are independent of each other, so a window deep into the
+/// expansion costs no more than computing those blocks alone. An empty vector
+/// is returned if `start_block > end_block` or `start_block` is negative.
+///
+/// Panics if `end_block` lies beyond `MAX_DIGITS / 9`, the number of blocks
+/// in [`MAX_DIGITS`] digits.
+pub fn compute_range(start_block: i32, end_block: i32) -> Vec<u32> {
+    let blocks = end_block.saturating_sub(start_block).max(0) as u32;
+    range_blocks(start_block, end_block, Tasks::default(), block_fn(blocks))
+}
+
+/* compute_range, with the blocks from block_at split into `tasks` */
+fn range_blocks(
+    start_block: i32,
+    end_block: i32,
+    tasks: Tasks,
+    block_at: fn(u32) -> u32,
+) -> Vec<u32> {
+    trace_span!(INFO, "compute_range", start_block, end_block);
+    if start_block < 0 || start_block > end_block {
+        return Vec::new();
+    }
+    prepare_blocks(end_block as u32);
+    par::map_blocks(start_block as u32..end_block as u32, tasks, block_at)
+}
+
+/// Return the text printed in front of `digits` fractional digits: `3.`, or
+/// just `3` when there are none, so that zero digits print as `3` rather
+/// than a dangling `3.`.
+pub fn pi_prefix(digits: u32) -> &'static str {
+    if digits == 0 {
+        "3"
+    } else {
+        "3."
+    }
+}
+
+/* format the leading blocks of `digits` digits as pi_prefix of the digits
+ * they hold followed by those digits, zero-padded and cut at `digits` */
+fn join_blocks(blocks: &[u32], digits: u32) -> String {
+    let kept = (9 * blocks.len() as u32).min(digits);
+    let prefix = pi_prefix(kept);
+    let mut buf = vec![0; prefix.len() + kept as usize];
+    let (head, rest) = buf.split_at_mut(prefix.len());
+    head.copy_from_slice(prefix.as_bytes());
+    for (b, chunk) in blocks.iter().zip(rest.chunks_mut(9)) {
+        write_block(*b, chunk);
+    }
+    String::from_utf8(buf).expect("digits are ASCII")
+}
+
+/* build pi_prefix(digits) followed by `digits` digits in a single buffer of
+ * the final size, writing each block from block_at(n) in place */
+fn digit_string<F>(digits: u32, tasks: Tasks, block_at: F) -> String
+where
+    F: Fn(u32) -> u32 + Sync + Send,
+{
+    trace_span!(INFO, "digits", digits);
+    let prefix = pi_prefix(digits);
+    let mut buf = vec![0; prefix.len() + digits as usize];
+    let (head, rest) = buf.split_at_mut(prefix.len());
+    head.copy_from_slice(prefix.as_bytes());
+    prepare_blocks(digits.div_ceil(9));
+    par::for_each_chunk(rest, 9, tasks, |n, chunk| write_block(block_at(n), chunk));
+    String::from_utf8(buf).expect("digits are ASCII")
+}
+
+/* write the leading out.len() digits of a zero-padded block as ASCII */
+pub(crate) fn write_block(b: u32, out: &mut [u8]) {
+    let mut rest = b;
+    let mut digits = [0u8; 9];
+    for d in digits.iter_mut().rev() {
+        *d = b'0' + (rest % 10) as u8;
+        rest /= 10;
+    }
+    out.copy_from_slice(&digits[..out.len()]);
+}
+
+/// Fill `buf` with ASCII digits of pi, starting with `b'3'` and continuing
+/// with the fractional digits without a decimal point. Returns the number of
+/// bytes written, which is always `buf.len()`.
+///
+/// Only the blocks overlapping `buf` are computed, and they are written
+/// straight into it without any intermediate allocation. If the buffer ends
+/// inside a block, only the leading digits of that block are written.
+/// Panics if `buf` is longer than one more than [`MAX_DIGITS`].
+pub fn fill_digits(buf: &mut [u8]) -> usize {
+    if let Some((first, rest)) = buf.split_first_mut() {
+        *first = b'3';
+        prepare_blocks(u32::try_from(rest.len().div_ceil(9)).unwrap_or(u32::MAX));
+        par::for_each_chunk(rest, 9, Tasks::default(), |n, chunk| {
+            write_block(block(n), chunk)
+        });
+    }
+    buf.len()
+}
+
+/// Compute `count` digits of pi in parallel and return them as a string
+/// prefixed with `3.`. This is the same as [`pi_string`].
+pub fn pi_digits(count: u32) -> String {
+    pi_string(count)
+}
+
+/// Return pi with exactly `digits` digits after the decimal point, e.g.
+/// `pi_string(4) == "3.1415"`. Zero digits give `"3"`.
+///
+/// Digits past [`max_accurate_digits`] are computed all the same, but may
+/// be wrong. Panics if `digits` is larger than [`MAX_DIGITS`]; use
+/// [`check_count`] to check a count first.
+///
+/// ```
+/// assert_eq!(parpi::pi_string(10), "3.1415926535");
+/// assert_eq!(parpi::pi_string(0), "3");
+/// ```
+pub fn pi_string(digits: u32) -> String {
+    digit_string(digits, Tasks::default(), block)
+}
+
+/// Like [`pi_string`], but calls `on_block(completed, total)` each time a
+/// block finishes, where `total` is the number of blocks being computed.
+///
+/// The callback runs on the worker threads. Blocks finish in whatever order
+/// they are scheduled, so invocations are not ordered: a call reporting
+/// `completed == 5` may arrive before the one reporting `4`. Every value
+/// from 1 to `total` is reported exactly once.
+pub fn compute_with_progress<F>(digits: u32, on_block: F) -> String
+where
+    F: Fn(u32, u32) + Sync,
+{
+    let num_blocks = digits.div_ceil(9);
+    let completed = AtomicU32::new(0);
+
+    digit_string(digits, Tasks::default(), |n| {
+        let b = block(n);
+        on_block(completed.fetch_add(1, Ordering::Relaxed) + 1, num_blocks);
+        b
+    })
+}
+
+/// Like [`pi_string`], but stops early once `cancel` is set.
+///
+/// The flag is checked before each block is started. Once it is seen, no
+/// further blocks are computed and the result holds every block up to the
+/// first one that was skipped, after [`pi_prefix`] of the digits they hold,
+/// so a cancelled run still returns a correct, untorn prefix of pi. If no
+/// block was done, that is just `3`.
+pub fn compute_cancellable(digits: u32, cancel: Arc<AtomicBool>) -> String {
+    let num_blocks = digits.div_ceil(9);
+
+    prepare_blocks(num_blocks);
+    let blocks: Vec<u32> = par::map_blocks(0..num_blocks, Tasks::default(), |n| {
+        if cancel.load(Ordering::Relaxed) {
+            None
+        } else {
+            Some(block(n))
+        }
+    })
+    .into_iter()
+    .map_while(|b| b)
+    .collect();
+    join_blocks(&blocks, digits)
+}
+
+/// Return the decimal digit of pi at `index`, where index 0 is the first
+/// digit after the decimal point.
+///
+/// Only the nine-digit block containing `index` is computed, so this is
+/// much cheaper than materializing every digit before it. The result is the
+/// digit the block computation yields; like every other entry point it is
+/// only as accurate as the `f64` accumulation in [`calc_digits`]. Panics
+/// unless `index < MAX_DIGITS`.
+///
+/// ```
+/// /* 3.14159... */
+/// assert_eq!(parpi::pi_digit_at(0), 1);
+/// assert_eq!(parpi::pi_digit_at(4), 9);
+/// ```
+pub fn pi_digit_at(index: u32) -> u8 {
+    let b = block_fn(1)(index / 9);
+    ((b / 10u32.pow(8 - index % 9)) % 10) as u8
+}
+
+/// Return the sum of the first `digits` digits of pi after the decimal
+/// point, e.g. `digit_sum(4) == 1 + 4 + 1 + 5`.
+///
+/// Each block is summed as soon as it is computed, so the digits are never
+/// held as a string. Panics if `digits` is larger than [`MAX_DIGITS`].
+///
+/// ```
+/// assert_eq!(parpi::digit_sum(10), 1 + 4 + 1 + 5 + 9 + 2 + 6 + 5 + 3 + 5);
+/// ```
+pub fn digit_sum(digits: u32) -> u64 {
+    sum_digits(digits, Tasks::default(), block_fn(digits.div_ceil(9)))
+}
+
+/* digit_sum, with the blocks from block_at split into `tasks` */
+fn sum_digits(digits: u32, tasks: Tasks, block_at: fn(u32) -> u32) -> u64 {
+    trace_span!(INFO, "digit_sum", digits);
+    let num_blocks = digits.div_ceil(9);
+    prepare_blocks(num_blocks);
+    par::map_blocks(0..num_blocks, tasks, |n| {
+        /* only the leading digits of the last block are counted */
+        let len = (digits - 9 * n).min(9);
+        let b = block_at(n) / 10u32.pow(9 - len);
+        let mut sum = 0;
+        let mut rest = b;
+        while rest > 0 {
+            sum += (rest % 10) as u64;
+            rest /= 10;
+        }
+        sum
+    })
+    .into_iter()
+    .sum()
+}
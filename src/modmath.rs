@@ -0,0 +1,266 @@
+/*
+ * Modular arithmetic and prime enumeration, as used by the digit
+ * extraction in calc_digits. The primes and exponents are always 32-bit;
+ * the moduli and the values reduced by them are 32-bit by default and may
+ * be 64-bit through ModInt.
+ */
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::{Add, Mul, Sub};
+
+/// Integer types the modular arithmetic of
+/// [`calc_digits_with`](crate::calc_digits_with) can run on.
+///
+/// `i32` is what [`calc_digits`](crate::calc_digits) uses: its products fit
+/// in an `i64` and its moduli are limited to below `2^30`, which bounds
+/// [`MAX_DIGITS`](crate::MAX_DIGITS). `i64` takes its products through an
+/// `i128`, which is slower but leaves room for far larger moduli.
+pub trait ModInt:
+    Copy + PartialOrd + From<i32> + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+    /// [`mul_mod`] for this type.
+    fn mul_mod(a: Self, b: Self, n: Self) -> Self;
+
+    /// [`inv_mod`] for this type.
+    fn inv_mod(x: Self, y: Self) -> Self;
+
+    /// [`inv_mod2`] for this type.
+    fn inv_mod2(u: Self, v: Self) -> Self;
+
+    /// Convert to the nearest `f64`.
+    fn to_f64(self) -> f64;
+}
+
+/* the same algorithms for every width, with the product taken in $wide */
+macro_rules! impl_mod_int {
+    ($t:ty, $wide:ty) => {
+        impl ModInt for $t {
+            #[inline]
+            fn mul_mod(a: $t, b: $t, n: $t) -> $t {
+                (((a as $wide) * (b as $wide)) % (n as $wide)) as $t
+            }
+
+            fn inv_mod(x: $t, y: $t) -> $t {
+                let mut u = x;
+                let mut v = y;
+                let mut c = 1;
+                let mut a = 0;
+                loop {
+                    let q = v / u;
+
+                    let mut t = c;
+                    c = a - q * c;
+                    a = t;
+
+                    t = u;
+                    u = v - q * u;
+                    v = t;
+                    if u == 0 {
+                        break;
+                    }
+                }
+
+                a %= y;
+                if a < 0 {
+                    a += y;
+                }
+                a
+            }
+
+            fn inv_mod2(u: $t, v: $t) -> $t {
+                let mut u1 = 1;
+                let mut u3 = u;
+
+                let mut v1 = v;
+                let mut v3 = v;
+
+                let mut t1;
+                let mut t3;
+                let mut skip = false;
+
+                if (u & 1) != 0 {
+                    t1 = 0;
+                    t3 = -v;
+                    skip = true;
+                } else {
+                    t1 = 1;
+                    t3 = u;
+                }
+
+                loop {
+                    loop {
+                        if !skip {
+                            if (t1 & 1) == 0 {
+                                t1 >>= 1;
+                                t3 >>= 1;
+                            } else {
+                                t1 = (t1 + v) >> 1;
+                                t3 >>= 1;
+                            }
+                        } else {
+                            skip = false;
+                        }
+
+                        if (t3 & 1) != 0 {
+                            break;
+                        }
+                    }
+
+                    if t3 >= 0 {
+                        u1 = t1;
+                        u3 = t3;
+                    } else {
+                        v1 = v - t1;
+                        v3 = -t3;
+                    }
+                    t1 = u1 - v1;
+                    t3 = u3 - v3;
+                    if t1 < 0 {
+                        t1 += v;
+                    }
+                    if t3 == 0 {
+                        break;
+                    }
+                }
+                u1
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+        }
+    };
+}
+
+impl_mod_int!(i32, i64);
+impl_mod_int!(i64, i128);
+
+/// Return `(a * b) mod n`, computed without overflow through a product of
+/// twice the width.
+///
+/// `n` must be positive. The remainder takes the sign of `a * b`, as `%`
+/// does: the result lies in `0..n` when `a * b` is non-negative, and in
+/// `-n + 1..=0` when it is negative, e.g. `mul_mod(-3, 5, 7) == -1`. The
+/// product of two `i32` values always fits in an `i64`, and that of two
+/// `i64` values in an `i128`, so this is exact for any operands.
+#[inline]
+pub fn mul_mod<T: ModInt>(a: T, b: T, n: T) -> T {
+    T::mul_mod(a, b, n)
+}
+
+/// Return `(a * b) mod n` for 64-bit operands, computed through an `i128`
+/// product. This is [`mul_mod`] on `i64`.
+pub fn mul_mod128(a: i64, b: i64, n: i64) -> i64 {
+    mul_mod(a, b, n)
+}
+
+/// Return the inverse of `x` modulo `y` using the extended Euclidean
+/// algorithm.
+///
+/// `x` must be non-zero and coprime to `y`, and `y` must be positive. The
+/// result lies in `0..y`. Unlike [`inv_mod2`] this works for even moduli.
+pub fn inv_mod<T: ModInt>(x: T, y: T) -> T {
+    T::inv_mod(x, y)
+}
+
+/// Return the inverse of `u` modulo `v` using the binary extended Euclidean
+/// algorithm.
+///
+/// `v` must be odd and below a quarter of the largest value of the type,
+/// `2^30` for `i32`, and `u` must be positive and coprime to `v`. The
+/// result lies in `0..v`.
+pub fn inv_mod2<T: ModInt>(u: T, v: T) -> T {
+    T::inv_mod2(u, v)
+}
+
+/// Return `a^b mod m` by square-and-multiply.
+///
+/// `b` must be non-negative and `m` positive. For `b == 0` the result is
+/// `1` without reduction, even when `m == 1`.
+pub fn pow_mod<T: ModInt>(a: T, mut b: i32, m: T) -> T {
+    let mut r = T::from(1);
+    let mut aa = a;
+    loop {
+        if (b & 1) != 0 {
+            r = mul_mod(r, aa, m);
+        }
+        b >>= 1;
+        if b == 0 {
+            break;
+        }
+        aa = mul_mod(aa, aa, m);
+    }
+    r
+}
+
+/// Return the largest `v` such that `a^v <= limit`, by repeated
+/// multiplication. `a` must be at least 2 and `limit` at least 1.
+///
+/// Unlike `ln(limit) / ln(a)` this is exact when `limit` is a power of `a`.
+pub fn max_power(a: i32, limit: i32) -> i32 {
+    let mut v = 0;
+    let mut p = a as i64;
+    while p <= limit as i64 {
+        p *= a as i64;
+        v += 1;
+    }
+    v
+}
+
+/// Return true if `n` is prime, by trial division. Numbers below 2,
+/// including all negative numbers, are not prime.
+pub fn is_prime(n: i32) -> bool {
+    if n < 2 {
+        return false;
+    }
+    if n == 2 {
+        return true;
+    }
+    if (n % 2) == 0 {
+        return false;
+    }
+
+    let mut i = 3;
+    loop {
+        /* i * i overflows an i32 for n close to i32::MAX */
+        if (i as i64) * (i as i64) > n as i64 {
+            return true;
+        }
+        if (n % i) == 0 {
+            return false;
+        }
+        i += 2;
+    }
+}
+
+/// Return the smallest prime greater than `n`.
+pub fn next_prime(mut n: i32) -> i32 {
+    loop {
+        n += 1;
+        if is_prime(n) {
+            return n;
+        }
+    }
+}
+
+/// Return every prime up to and including `limit`, in increasing order,
+/// using the sieve of Eratosthenes.
+pub fn sieve(limit: i32) -> Vec<i32> {
+    if limit < 2 {
+        return Vec::new();
+    }
+    let limit = limit as usize;
+    let mut composite = vec![false; limit + 1];
+    let mut primes = Vec::new();
+    for i in 2..=limit {
+        if composite[i] {
+            continue;
+        }
+        primes.push(i as i32);
+        for j in (i * i..=limit).step_by(i) {
+            composite[j] = true;
+        }
+    }
+    primes
+}
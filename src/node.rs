@@ -0,0 +1,50 @@
+/*
+ * Node.js bindings through napi-rs. The cdylib built with the node feature
+ * loads as a native addon once copied to a `.node` file; see
+ * examples/node/pi.js.
+ */
+
+use napi::bindgen_prelude::{AsyncTask, Env, Error, Result, Task};
+use napi_derive::napi;
+
+use crate::PiComputer;
+
+fn compute(digits: u32) -> Result<String> {
+    PiComputer::new()
+        .digits(digits)
+        .build()
+        .compute()
+        .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+/// Return pi with exactly `digits` digits after the decimal point,
+/// blocking the calling JavaScript thread until it is done.
+#[napi]
+pub fn pi(digits: u32) -> Result<String> {
+    compute(digits)
+}
+
+/// The computation behind [`pi_async`], run on the libuv thread pool.
+pub struct PiTask {
+    digits: u32,
+}
+
+impl Task for PiTask {
+    type Output = String;
+    type JsValue = String;
+
+    fn compute(&mut self) -> Result<String> {
+        compute(self.digits)
+    }
+
+    fn resolve(&mut self, _env: Env, output: String) -> Result<String> {
+        Ok(output)
+    }
+}
+
+/// Like [`pi`], but return a promise and compute the digits off the
+/// JavaScript thread. Exported to JavaScript as `piAsync`.
+#[napi]
+pub fn pi_async(digits: u32) -> AsyncTask<PiTask> {
+    AsyncTask::new(PiTask { digits })
+}
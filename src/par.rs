@@ -0,0 +1,409 @@
+/*
+ * Every block of digits is independent, so all parallelism in the crate
+ * comes down to mapping a function over a range of block indices. With the
+ * `rayon` feature this runs on the rayon pool, without it it is a plain
+ * serial loop producing the same output.
+ *
+ * The cost of a block grows with its index, roughly quadratically, so
+ * evenly sized chunks of the block range leave the threads holding the
+ * early, cheap blocks idle while the last chunk finishes. Even the
+ * cheapest block takes far longer than scheduling a rayon task, so the
+ * range is split down to single blocks and idle threads steal work one
+ * block at a time.
+ *
+ * When there are fewer blocks than threads, the sum over primes inside
+ * each block is split up as well.
+ *
+ * A chunk size set through PiComputerBuilder::chunk_size overrides the
+ * single block per task, and is passed down to every block range split up
+ * below as Tasks, along with the window of the ordered stream.
+ */
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::float;
+
+/* how block ranges are split into parallel tasks */
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Tasks {
+    /* blocks per task, or 0 for the default of one */
+    pub(crate) chunk: u32,
+    /* blocks streamed at a time by for_each_ordered, or 0 for the
+     * default picked in stream.rs */
+    pub(crate) window: u32,
+}
+
+impl Tasks {
+    /* the number of blocks in each task */
+    #[cfg_attr(not(feature = "rayon"), allow(dead_code))]
+    fn len(self) -> u32 {
+        self.chunk.max(1)
+    }
+}
+
+#[cfg(feature = "rayon")]
+pub(crate) fn map_blocks<T, F>(blocks: Range<u32>, tasks: Tasks, f: F) -> Vec<T>
+where
+    T: Send,
+    F: Fn(u32) -> T + Sync + Send,
+{
+    use rayon::prelude::*;
+    let len = tasks.len() as usize;
+    blocks
+        .into_par_iter()
+        .with_min_len(len)
+        .with_max_len(len)
+        .map(f)
+        .collect()
+}
+
+#[cfg(not(feature = "rayon"))]
+pub(crate) fn map_blocks<T, F>(blocks: Range<u32>, _tasks: Tasks, f: F) -> Vec<T>
+where
+    T: Send,
+    F: Fn(u32) -> T + Sync + Send,
+{
+    blocks.map(f).collect()
+}
+
+/* call f(i, chunk) for every chunk of at most `len` bytes of buf */
+#[cfg(feature = "rayon")]
+pub(crate) fn for_each_chunk<F>(buf: &mut [u8], len: usize, tasks: Tasks, f: F)
+where
+    F: Fn(u32, &mut [u8]) + Sync + Send,
+{
+    use rayon::prelude::*;
+    let chunks = tasks.len() as usize;
+    buf.par_chunks_mut(len)
+        .with_min_len(chunks)
+        .with_max_len(chunks)
+        .enumerate()
+        .for_each(|(i, c)| f(i as u32, c));
+}
+
+#[cfg(not(feature = "rayon"))]
+pub(crate) fn for_each_chunk<F>(buf: &mut [u8], len: usize, _tasks: Tasks, f: F)
+where
+    F: Fn(u32, &mut [u8]) + Sync + Send,
+{
+    buf.chunks_mut(len)
+        .enumerate()
+        .for_each(|(i, c)| f(i as u32, c));
+}
+
+/*
+ * Call emit(i, compute(i)) for every i in blocks, in order, with at most
+ * `window` blocks computed or waiting to be emitted at any time.
+ *
+ * One worker per thread claims the next task of blocks, one block or a
+ * chunk of tasks.chunk blocks, whenever the window has room for it.
+ * Finished blocks wait in a reorder buffer until every block before them
+ * has been emitted, so a slow block only holds back the blocks behind it
+ * rather than a whole batch. The workers are spawned once per call, so the
+ * allocations made do not grow with the number of blocks.
+ *
+ * emit runs on the calling thread. That thread may itself be a pool
+ * thread, possibly the only one, in which case it claims and computes
+ * tasks too rather than waiting on workers that may never get to run.
+ * compute must not use rayon itself: waiting on it, the calling thread
+ * could pick up a worker that waits for the window to move on.
+ */
+#[cfg(feature = "rayon")]
+pub(crate) fn for_each_ordered<T, E, C, F>(
+    blocks: Range<u32>,
+    window: u32,
+    tasks: Tasks,
+    compute: C,
+    mut emit: F,
+) -> Result<(), E>
+where
+    T: Send,
+    C: Fn(u32) -> T + Sync,
+    F: FnMut(u32, T) -> Result<(), E>,
+{
+    use std::collections::VecDeque;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::sync::{mpsc, Condvar, Mutex};
+
+    /* the blocks not yet claimed start at `next`; a task may be claimed
+     * once it ends no later than `limit`, the end of the window */
+    struct Claims {
+        next: u32,
+        limit: u32,
+        stopped: bool,
+    }
+
+    /* claim the next task, waiting for room in the window if `wait`;
+     * None once every block is claimed or the emitting side is gone */
+    fn claim(
+        state: &(Mutex<Claims>, Condvar),
+        end: u32,
+        len: u32,
+        wait: bool,
+    ) -> Option<Range<u32>> {
+        let mut claims = state.0.lock().unwrap();
+        loop {
+            if claims.stopped || claims.next >= end {
+                return None;
+            }
+            let task = claims.next..end.min(claims.next.saturating_add(len));
+            if task.end <= claims.limit {
+                claims.next = task.end;
+                return Some(task);
+            }
+            if !wait {
+                return None;
+            }
+            claims = state.1.wait(claims).unwrap();
+        }
+    }
+
+    /* stops the workers however the emitting side returns, so that the
+     * scope never waits on a worker waiting for room in the window */
+    struct Stop<'a>(&'a (Mutex<Claims>, Condvar));
+
+    impl Drop for Stop<'_> {
+        fn drop(&mut self) {
+            self.0 .0.lock().unwrap().stopped = true;
+            self.0 .1.notify_all();
+        }
+    }
+
+    /* a window always holds at least one whole task, and with at most
+     * `window` blocks outstanding sends never block */
+    let len = tasks.len();
+    let window = window.max(len);
+    let start = blocks.start;
+    let end = blocks.end;
+    let state = (
+        Mutex::new(Claims {
+            next: start,
+            limit: start.saturating_add(window),
+            stopped: false,
+        }),
+        Condvar::new(),
+    );
+    let (tx, rx) = mpsc::sync_channel(window as usize);
+    let on_pool = rayon::current_thread_index().is_some();
+    let workers = rayon::current_num_threads() - on_pool as usize;
+
+    rayon::in_place_scope(|scope| {
+        let stop = Stop(&state);
+        for _ in 0..workers {
+            let tx = tx.clone();
+            let (state, compute) = (&state, &compute);
+            /* a panic in a spawned task would only resurface once the
+             * scope ends, so it is caught and resumed by the emitting
+             * side instead */
+            scope.spawn(move |_| {
+                while let Some(task) = claim(state, end, len, true) {
+                    trace_span!(DEBUG, "task", start = task.start, end = task.end);
+                    for i in task {
+                        let value = panic::catch_unwind(AssertUnwindSafe(|| compute(i)));
+                        let failed = value.is_err();
+                        if tx.send((i, value)).is_err() || failed {
+                            return;
+                        }
+                    }
+                }
+            });
+        }
+
+        /* pending[k] holds block next_emit + k once it is done */
+        let mut pending = VecDeque::with_capacity(window as usize);
+        let mut next_emit = start;
+        while next_emit < end {
+            if let Some(Some(_)) = pending.front() {
+                while let Some(Some(_)) = pending.front() {
+                    let value = pending.pop_front().flatten().unwrap();
+                    emit(next_emit, value)?;
+                    next_emit += 1;
+                }
+                state.0.lock().unwrap().limit = next_emit.saturating_add(window);
+                state.1.notify_all();
+                continue;
+            }
+            let (i, value) = match rx.try_recv() {
+                Ok(done) => done,
+                Err(_) => match on_pool.then(|| claim(&state, end, len, false)).flatten() {
+                    Some(task) => {
+                        for i in task {
+                            let k = (i - next_emit) as usize;
+                            pending.resize_with(pending.len().max(k + 1), || None);
+                            pending[k] = Some(compute(i));
+                        }
+                        continue;
+                    }
+                    /* every block in the window is claimed, and the ones
+                     * not done are running on other threads */
+                    None => rx.recv().expect("a sender is held above"),
+                },
+            };
+            let k = (i - next_emit) as usize;
+            pending.resize_with(pending.len().max(k + 1), || None);
+            pending[k] = Some(value.unwrap_or_else(|e| panic::resume_unwind(e)));
+        }
+        drop(stop);
+        Ok(())
+    })
+}
+
+#[cfg(not(feature = "rayon"))]
+pub(crate) fn for_each_ordered<T, E, C, F>(
+    blocks: Range<u32>,
+    _window: u32,
+    _tasks: Tasks,
+    compute: C,
+    mut emit: F,
+) -> Result<(), E>
+where
+    T: Send,
+    C: Fn(u32) -> T + Sync,
+    F: FnMut(u32, T) -> Result<(), E>,
+{
+    for i in blocks {
+        emit(i, compute(i))?;
+    }
+    Ok(())
+}
+
+/* add up f(a) for every a in items, modulo 1 */
+#[cfg(feature = "rayon")]
+pub(crate) fn sum_fracs<F>(items: &[i32], f: F) -> f64
+where
+    F: Fn(i32) -> f64 + Sync + Send,
+{
+    use rayon::prelude::*;
+    items
+        .par_iter()
+        .map(|&a| f(a))
+        .reduce(|| 0.0, |x, y| float::fract(x + y))
+}
+
+#[cfg(not(feature = "rayon"))]
+pub(crate) fn sum_fracs<F>(items: &[i32], f: F) -> f64
+where
+    F: Fn(i32) -> f64 + Sync + Send,
+{
+    items
+        .iter()
+        .map(|&a| f(a))
+        .fold(0.0, |x, y| float::fract(x + y))
+}
+
+#[cfg(feature = "rayon")]
+pub(crate) fn num_threads() -> usize {
+    rayon::current_num_threads()
+}
+
+#[cfg(not(feature = "rayon"))]
+pub(crate) fn num_threads() -> usize {
+    1
+}
+
+#[cfg(all(test, feature = "rayon"))]
+mod tests {
+    use super::{for_each_ordered, Tasks};
+    use std::panic::{self, AssertUnwindSafe};
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Mutex;
+    use std::thread;
+    use std::time::Duration;
+
+    const BLOCKS: u32 = 24;
+
+    fn pool(threads: usize) -> rayon::ThreadPool {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap()
+    }
+
+    /* block i takes longer the earlier it is, so with more than one thread
+     * the later blocks of a window are done before the ones ahead of them */
+    fn slow_start(i: u32) -> u32 {
+        thread::sleep(Duration::from_millis(2 * (BLOCKS - i) as u64));
+        i
+    }
+
+    #[test]
+    fn emits_in_order_whatever_the_completion_order() {
+        for threads in [1, 4] {
+            for (window, chunk) in [(1, 1), (4, 1), (8, 1), (8, 3), (64, 1)] {
+                let done = Mutex::new(Vec::new());
+                let mut emitted = Vec::new();
+                let tasks = Tasks { chunk, window: 0 };
+                let compute = |i| {
+                    let value = slow_start(i);
+                    done.lock().unwrap().push(i);
+                    value
+                };
+                let result = pool(threads).install(|| {
+                    for_each_ordered(0..BLOCKS, window, tasks, compute, |i, value| {
+                        assert_eq!(value, i);
+                        emitted.push(i);
+                        Ok::<_, ()>(())
+                    })
+                });
+                let what = format!("{} threads, window {}, chunk {}", threads, window, chunk);
+                assert_eq!(result, Ok(()), "{}", what);
+                assert_eq!(emitted, (0..BLOCKS).collect::<Vec<_>>(), "{}", what);
+                /* the reorder buffer was really needed */
+                let done = done.into_inner().unwrap();
+                if threads > 1 && window > 1 {
+                    assert!(done.windows(2).any(|w| w[0] > w[1]), "{}", what);
+                }
+            }
+        }
+    }
+
+    /* an error from emit is returned, and no block is started beyond the
+     * window at the time */
+    #[test]
+    fn emit_error_stops_the_workers() {
+        let started = AtomicU32::new(0);
+        let result = pool(4).install(|| {
+            let compute = |i| {
+                started.fetch_add(1, Ordering::Relaxed);
+                slow_start(i)
+            };
+            for_each_ordered(0..BLOCKS, 4, Tasks::default(), compute, |i, _| {
+                if i == 5 {
+                    Err(i)
+                } else {
+                    Ok(())
+                }
+            })
+        });
+        assert_eq!(result, Err(5));
+        assert!(
+            started.into_inner() <= 5 + 4,
+            "blocks started past the window"
+        );
+    }
+
+    #[test]
+    fn compute_panic_reaches_the_caller() {
+        for threads in [1, 4] {
+            let pool = pool(threads);
+            let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                pool.install(|| {
+                    let compute = |i| {
+                        if i == 3 {
+                            panic!("block {} failed", i);
+                        }
+                        slow_start(i)
+                    };
+                    for_each_ordered(0..BLOCKS, 8, Tasks::default(), compute, |i, _| {
+                        assert!(i < 3, "block {} emitted after the panic", i);
+                        Ok::<_, ()>(())
+                    })
+                })
+            }));
+            let payload = result.unwrap_err();
+            let message = payload.downcast_ref::<String>().unwrap();
+            assert_eq!(message, "block 3 failed", "{} threads", threads);
+        }
+    }
+}
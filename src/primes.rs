@@ -0,0 +1,49 @@
+/*
+ * Every block walks all primes up to 3 * nl, which is nearly the same bound
+ * for neighbouring blocks. The primes are sieved once and shared by all
+ * blocks and threads; the list is only sieved again when a block needs a
+ * larger bound than any before it.
+ *
+ * Without std there is no lock to guard a shared list, so every block sieves
+ * its own; the sieve is linear in the bound while the block is quadratic.
+ */
+
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::sync::RwLock;
+
+use crate::modmath::sieve;
+
+/* the sieved bound and the primes up to it */
+#[cfg(feature = "std")]
+static PRIMES: RwLock<Option<(i32, Arc<Vec<i32>>)>> = RwLock::new(None);
+
+/* return a list starting with every prime up to and including limit */
+#[cfg(feature = "std")]
+pub(crate) fn primes_up_to(limit: i32) -> Arc<Vec<i32>> {
+    if let Some((bound, primes)) = PRIMES.read().unwrap().as_ref() {
+        if *bound >= limit {
+            return primes.clone();
+        }
+    }
+    let mut cache = PRIMES.write().unwrap();
+    if let Some((bound, primes)) = cache.as_ref() {
+        if *bound >= limit {
+            return primes.clone();
+        }
+    }
+    /* grow geometrically so a slowly rising bound is not sieved every time */
+    let bound = match cache.as_ref() {
+        Some((old, _)) => limit.max(old.saturating_mul(2)),
+        None => limit,
+    };
+    let primes = Arc::new(sieve(bound));
+    *cache = Some((bound, primes.clone()));
+    primes
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn primes_up_to(limit: i32) -> Arc<Vec<i32>> {
+    Arc::new(sieve(limit))
+}
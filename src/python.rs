@@ -0,0 +1,52 @@
+/*
+ * Python bindings through PyO3, built into an extension module with
+ * maturin (see pyproject.toml). The computation does not touch any Python
+ * objects, so it runs with the GIL released and other Python threads keep
+ * running meanwhile.
+ *
+ * A panic would surface in Python as a PanicException rather than an
+ * ordinary error, so input the Rust functions would panic on is rejected
+ * up front with a ValueError, as the C ABI in ffi.rs rejects it too. The
+ * #[pyfunction] expansion converts the PyErr of such a PyResult into
+ * itself, which clippy flags; that conversion is PyO3's, not ours.
+ */
+
+#![allow(clippy::useless_conversion)]
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::{check_count, MAX_DIGITS};
+
+/// Return pi with exactly `digits` digits after the decimal point.
+///
+/// Raises `ValueError` if `digits` is larger than `MAX_DIGITS`.
+#[pyfunction]
+fn pi_string(py: Python<'_>, digits: u32) -> PyResult<String> {
+    check_count(digits).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    Ok(py.allow_threads(|| crate::pi_string(digits)))
+}
+
+/// Return the decimal digit of pi at `index`, where index 0 is the first
+/// digit after the decimal point.
+///
+/// Raises `ValueError` unless `index` is below `MAX_DIGITS`.
+#[pyfunction]
+fn pi_digit_at(py: Python<'_>, index: u32) -> PyResult<u8> {
+    if index >= MAX_DIGITS {
+        return Err(PyValueError::new_err(format!(
+            "index {} is too large, only the first {} digits are supported",
+            index, MAX_DIGITS
+        )));
+    }
+    Ok(py.allow_threads(|| crate::pi_digit_at(index)))
+}
+
+/// Digits of pi computed in parallel.
+#[pymodule]
+fn parpi(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("MAX_DIGITS", MAX_DIGITS)?;
+    m.add_function(wrap_pyfunction!(pi_string, m)?)?;
+    m.add_function(wrap_pyfunction!(pi_digit_at, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use alloc::string::String;
+use serde::Serialize;
+
+use crate::pi_string;
+
+/// Digits of pi together with their count, for returning as structured data
+/// such as JSON.
+///
+/// This is also the shape of the `--format json` output of the command line
+/// tool, where `digits` may leave out the `3.` or be split into groups.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PiResult {
+    /// Pi as text, as returned by [`pi_string`].
+    pub digits: String,
+    /// The number of digits after the decimal point.
+    pub count: u32,
+}
+
+impl PiResult {
+    /// Compute pi with exactly `count` digits after the decimal point.
+    pub fn new(count: u32) -> Self {
+        PiResult {
+            digits: pi_string(count),
+            count,
+        }
+    }
+}
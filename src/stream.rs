@@ -0,0 +1,93 @@
+#[cfg(feature = "std")]
+use std::io::{self, Write};
+
+use crate::par::{self, Tasks};
+use crate::prepare_blocks;
+#[cfg(feature = "std")]
+use crate::{block, pi_prefix, write_block};
+
+/* blocks computed or waiting to be written at any time: tasks.window, or
+ * by default four per thread */
+fn window_len(tasks: Tasks) -> u32 {
+    match tasks.window {
+        0 => 4 * par::num_threads() as u32,
+        len => len,
+    }
+}
+
+/* compute blocks 0..num_blocks with block_at in parallel, split into
+ * `tasks`, passing each to `f` in order as soon as it and every block before
+ * it are done */
+pub(crate) fn for_each_block<E, F>(
+    num_blocks: u32,
+    tasks: Tasks,
+    block_at: fn(u32) -> u32,
+    f: F,
+) -> Result<(), E>
+where
+    F: FnMut(u32, u32) -> Result<(), E>,
+{
+    trace_span!(INFO, "stream", num_blocks);
+    prepare_blocks(num_blocks);
+    par::for_each_ordered(0..num_blocks, window_len(tasks), tasks, block_at, f)
+}
+
+/// Write exactly `digits` digits of pi after the decimal point to `w`,
+/// without the leading `3.`.
+///
+/// Blocks are written as soon as they and every block before them are
+/// done, so only a small window of blocks is held in memory no matter how
+/// many digits are requested. Panics if `digits` is larger than
+/// [`MAX_DIGITS`](crate::MAX_DIGITS).
+#[cfg(feature = "std")]
+pub fn write_digits_to<W: Write>(w: &mut W, digits: u32) -> io::Result<()> {
+    write_digits(w, digits, Tasks::default(), block)
+}
+
+/* write_digits_to, with the blocks from block_at split into `tasks` */
+#[cfg(feature = "std")]
+pub(crate) fn write_digits<W: Write>(
+    w: &mut W,
+    digits: u32,
+    tasks: Tasks,
+    block_at: fn(u32) -> u32,
+) -> io::Result<()> {
+    let mut remaining = digits as usize;
+    for_each_block(digits.div_ceil(9), tasks, block_at, |_, b| {
+        let mut buf = [0; 9];
+        let n = remaining.min(9);
+        remaining -= n;
+        write_block(b, &mut buf[..n]);
+        w.write_all(&buf[..n])
+    })
+}
+
+/// Write `3.` followed by exactly `digits` digits of pi to `w`, streaming
+/// blocks like [`write_digits_to`]. Zero digits write just `3`.
+#[cfg(feature = "std")]
+pub fn write_pi_to<W: Write>(w: &mut W, digits: u32) -> io::Result<()> {
+    w.write_all(pi_prefix(digits).as_bytes())?;
+    write_digits_to(w, digits)
+}
+
+/// Return an asynchronous stream of the nine-digit blocks covering `digits`
+/// digits after the decimal point.
+///
+/// The blocks are computed on a background thread and delivered through a
+/// channel in index order as soon as they are ready. The last block is
+/// yielded whole, so it may extend past `digits`. Dropping the stream stops
+/// the computation after the current window of blocks.
+#[cfg(feature = "async")]
+pub fn pi_stream(digits: u32) -> impl futures::Stream<Item = u32> {
+    let (tx, rx) = futures::channel::mpsc::unbounded();
+    let work = move || {
+        let _ = for_each_block(digits.div_ceil(9), Tasks::default(), block, |_, b| {
+            tx.unbounded_send(b)
+        });
+    };
+    #[cfg(feature = "rayon")]
+    rayon::spawn(work);
+    #[cfg(not(feature = "rayon"))]
+    std::thread::spawn(work);
+    rx
+}
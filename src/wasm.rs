@@ -0,0 +1,24 @@
+/*
+ * Bindings for JavaScript through wasm-bindgen. Threads are not available
+ * to wasm32-unknown-unknown by default, so this is built without the rayon
+ * feature and everything runs on the serial fallback in par.rs:
+ *
+ *   cargo build --target wasm32-unknown-unknown --no-default-features --features wasm
+ */
+
+use wasm_bindgen::prelude::*;
+
+#[cfg(all(target_arch = "wasm32", feature = "rayon"))]
+compile_error!("build for wasm32 with --no-default-features, rayon needs threads");
+
+/// Return pi with exactly `digits` digits after the decimal point, as
+/// [`pi_string`](crate::pi_string) does.
+///
+/// A count beyond [`MAX_DIGITS`](crate::MAX_DIGITS) is thrown to the
+/// caller as a JavaScript `Error` with the message of
+/// [`ParpiError::CountTooLarge`](crate::ParpiError::CountTooLarge).
+#[wasm_bindgen]
+pub fn pi(digits: u32) -> Result<String, JsError> {
+    crate::check_count(digits)?;
+    Ok(crate::pi_string(digits))
+}
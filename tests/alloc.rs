@@ -0,0 +1,53 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::io;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/* count every allocation made by the test binary, on any thread */
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    f();
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+/* a single test, so that no other test allocates while this one counts */
+#[test]
+fn allocations_per_block() {
+    /* warm up the thread pool and the prime list */
+    parpi::pi_string(900);
+
+    let small = allocations(|| drop(parpi::pi_string(90)));
+    let large = allocations(|| drop(parpi::pi_string(900)));
+    println!(
+        "pi_string: {} allocations for 90 digits, {} for 900",
+        small, large
+    );
+    assert!(large <= small, "{} > {}", large, small);
+
+    /* the streaming writer spawns one worker per thread, however many
+     * blocks there are */
+    let small = allocations(|| parpi::write_digits_to(&mut io::sink(), 90).unwrap());
+    let large = allocations(|| parpi::write_digits_to(&mut io::sink(), 900).unwrap());
+    println!(
+        "write_digits_to: {} allocations for 90 digits, {} for 900",
+        small, large
+    );
+    assert!(large <= small, "{} > {}", large, small);
+}
@@ -0,0 +1,32 @@
+use parpi::bbp::hex_digit_at;
+
+#[test]
+fn first_hex_digits() {
+    let hex: String = (0..32)
+        .map(|i| char::from_digit(hex_digit_at(i) as u32, 16).unwrap())
+        .collect();
+    assert_eq!(hex, "243f6a8885a308d313198a2e03707344");
+}
+
+/*
+ * Convert the first 999 decimal digits to hex by repeatedly multiplying
+ * the fraction by 16, with exact arithmetic on the nine-digit blocks. The
+ * truncated fraction is within 1e-999 of pi, which pins down about 829 hex
+ * digits, so the first 800 must agree with BBP.
+ */
+#[test]
+fn decimal_digits_agree_with_bbp() {
+    let mut blocks: Vec<u64> = parpi::compute_range(0, 111)
+        .into_iter()
+        .map(u64::from)
+        .collect();
+    for i in 0..800 {
+        let mut carry = 0;
+        for b in blocks.iter_mut().rev() {
+            let v = *b * 16 + carry;
+            *b = v % 1_000_000_000;
+            carry = v / 1_000_000_000;
+        }
+        assert_eq!(carry, hex_digit_at(i) as u64, "hex digit {}", i);
+    }
+}
@@ -0,0 +1,46 @@
+use parpi::bcd::{decode, BcdWriter};
+use std::io::Write;
+
+fn encode(digits: &str) -> Vec<u8> {
+    let mut w = BcdWriter::new(Vec::new());
+    w.write_all(digits.as_bytes()).unwrap();
+    w.finish().unwrap()
+}
+
+#[test]
+fn layout() {
+    assert_eq!(encode(""), []);
+    assert_eq!(encode("3"), [0x3F]);
+    assert_eq!(encode("31415"), [0x31, 0x41, 0x5F]);
+    assert_eq!(encode("314159"), [0x31, 0x41, 0x59]);
+}
+
+#[test]
+fn round_trip() {
+    for n in 0..=parpi::REFERENCE_DIGITS.len() {
+        let digits = &parpi::REFERENCE_DIGITS[..n];
+        let packed = encode(digits);
+        assert_eq!(packed.len(), n.div_ceil(2));
+        assert_eq!(decode(&packed).as_deref(), Some(digits));
+    }
+}
+
+/* digits arriving in arbitrary pieces pack the same as all at once */
+#[test]
+fn split_writes() {
+    let mut w = BcdWriter::new(Vec::new());
+    for piece in ["3", "14", "1", "592", "65"] {
+        w.write_all(piece.as_bytes()).unwrap();
+    }
+    assert_eq!(w.finish().unwrap(), encode("314159265"));
+}
+
+#[test]
+fn invalid_input() {
+    let mut w = BcdWriter::new(Vec::new());
+    assert!(w.write_all(b"3.14").is_err());
+    assert_eq!(decode(&[0x3A]), None);
+    assert_eq!(decode(&[0xF1]), None);
+    assert_eq!(decode(&[0x3F, 0x14]), None);
+    assert_eq!(decode(&[0x14, 0x3F]).as_deref(), Some("143"));
+}
@@ -0,0 +1,816 @@
+#![cfg(feature = "cli")]
+
+use std::process::Command;
+
+fn parpi_bytes(args: &[&str]) -> Vec<u8> {
+    let out = Command::new(env!("CARGO_BIN_EXE_parpi"))
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    out.stdout
+}
+
+fn parpi(args: &[&str]) -> String {
+    String::from_utf8(parpi_bytes(args)).unwrap()
+}
+
+#[test]
+fn group_digits() {
+    assert_eq!(
+        parpi(&["36", "--group", "10"]),
+        "3.1415926535 8979323846 2643383279 502884\n"
+    );
+}
+
+#[test]
+fn exact_digit_counts() {
+    for &n in &[1, 8, 9, 10, 1000] {
+        let out = parpi(&[&n.to_string()]);
+        let digits = out.trim_end().strip_prefix("3.").unwrap();
+        assert_eq!(digits.len(), n);
+        assert_eq!(digits, &parpi::REFERENCE_DIGITS[..n]);
+    }
+}
+
+#[test]
+fn zero_digits() {
+    assert_eq!(parpi(&["0"]), "3\n");
+    assert_eq!(
+        parpi(&["0", "--format", "json"]),
+        "{\"digits\":\"3\",\"count\":0}\n"
+    );
+}
+
+#[test]
+fn json_matches_pi_result() {
+    for n in [0, 1, 100] {
+        let json = serde_json::to_string(&parpi::PiResult::new(n)).unwrap();
+        assert_eq!(parpi(&[&n.to_string(), "--format", "json"]), json + "\n");
+    }
+    assert_eq!(
+        parpi(&["12", "--format", "json", "--no-prefix", "--group", "5"]),
+        "{\"digits\":\"14159 26535 89\",\"count\":12}\n"
+    );
+}
+
+#[test]
+fn output_file_matches_stdout() {
+    let path = std::env::temp_dir().join(format!("parpi-output-{}.txt", std::process::id()));
+    let file = path.to_str().unwrap();
+    for args in [
+        &["0"][..],
+        &["100"],
+        &["100", "--group", "10"],
+        &["37", "--group", "4", "--format", "json"],
+        &["20", "--format", "raw"],
+        &["30", "--format", "rust"],
+        &["30", "--format", "c-header", "--no-prefix"],
+    ] {
+        let stdout = parpi(args);
+        assert_eq!(parpi(&[args, &["--output", file]].concat()), "");
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            stdout,
+            "{:?}",
+            args
+        );
+    }
+    std::fs::remove_file(&path).unwrap();
+}
+
+/* decoding the base64 gives back exactly what --format raw prints, and
+ * the same comes out of a file */
+#[test]
+fn base64_round_trips() {
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+
+    let path = std::env::temp_dir().join(format!("parpi-base64-{}.txt", std::process::id()));
+    for n in ["0", "1", "2", "3", "100", "1000"] {
+        let out = parpi(&[n, "--format", "base64"]);
+        let raw = parpi(&[n, "--format", "raw"]);
+        assert_eq!(
+            STANDARD.decode(out.trim_end()).unwrap(),
+            raw.trim_end().as_bytes()
+        );
+
+        let file = path.to_str().unwrap();
+        assert_eq!(parpi(&[n, "--format", "base64", "--output", file]), "");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), out);
+    }
+    std::fs::remove_file(&path).unwrap();
+}
+
+/* the MessagePack map decodes to the same value as the JSON object */
+#[cfg(feature = "msgpack")]
+#[test]
+fn msgpack_matches_json() {
+    for args in [
+        &["0"][..],
+        &["20"],
+        &["100", "--no-prefix"],
+        &["37", "--group", "4"],
+    ] {
+        let packed = parpi_bytes(&[args, &["--format", "msgpack"]].concat());
+        let value: serde_json::Value = rmp_serde::from_slice(&packed).unwrap();
+        let json = parpi(&[args, &["--format", "json"]].concat());
+        assert_eq!(
+            value,
+            serde_json::from_str::<serde_json::Value>(&json).unwrap(),
+            "{:?}",
+            args
+        );
+    }
+}
+
+#[test]
+fn stream_matches_batch() {
+    for args in [&["0"][..], &["100"], &["500", "--group", "7"]] {
+        let batch = parpi(args);
+        assert_eq!(parpi(&[args, &["--stream"]].concat()), batch, "{:?}", args);
+    }
+}
+
+#[test]
+fn jsonl_blocks() {
+    assert_eq!(
+        parpi(&["20", "--format", "jsonl"]),
+        concat!(
+            "{\"index\": 0, \"digits\": \"141592653\"}\n",
+            "{\"index\": 1, \"digits\": \"589793238\"}\n",
+            "{\"index\": 2, \"digits\": \"46\"}\n",
+        )
+    );
+    assert_eq!(parpi(&["0", "--format", "jsonl"]), "");
+
+    let out = parpi(&["1000", "--format", "jsonl"]);
+    let digits: String = out
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let prefix = format!("{{\"index\": {}, \"digits\": \"", i);
+            line.strip_prefix(&prefix)
+                .unwrap()
+                .strip_suffix("\"}")
+                .unwrap()
+        })
+        .collect();
+    assert_eq!(digits, parpi::REFERENCE_DIGITS);
+}
+
+#[test]
+fn bcd_decodes_to_decimal() {
+    for n in ["0", "1", "2", "1000"] {
+        let decimal = parpi(&[n]).trim_end().replace('.', "");
+        let packed = parpi_bytes(&[n, "--format", "bcd"]);
+        assert_eq!(parpi::bcd::decode(&packed), Some(decimal));
+    }
+    assert_eq!(
+        parpi_bytes(&["3", "--format", "bcd", "--no-prefix"]),
+        [0x14, 0x1F]
+    );
+}
+
+#[test]
+fn serve_digit_ranges() {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpStream;
+    use std::process::Stdio;
+
+    let mut server = Command::new(env!("CARGO_BIN_EXE_parpi"))
+        .args(["--serve", "127.0.0.1:0"])
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut banner = String::new();
+    BufReader::new(server.stderr.take().unwrap())
+        .read_line(&mut banner)
+        .unwrap();
+    let addr = banner.trim().rsplit(' ').next().unwrap();
+
+    let mut conn = TcpStream::connect(addr).unwrap();
+    conn.write_all(b"0 10\n995 10\n1 0\nfoo\n").unwrap();
+    conn.shutdown(std::net::Shutdown::Write).unwrap();
+    let mut lines = BufReader::new(conn).lines().map(Result::unwrap);
+    let pi = parpi::pi_string(1005);
+    assert_eq!(lines.next().unwrap(), "1415926535");
+    assert_eq!(lines.next().unwrap(), &pi[2 + 995..]);
+    assert_eq!(lines.next().unwrap(), "");
+    assert!(lines.next().unwrap().starts_with("error: "));
+    assert!(lines.next().is_none());
+
+    server.kill().unwrap();
+    server.wait().unwrap();
+}
+
+/* a reader sees the first blocks long before 100K digits are done, and
+ * closing the FIFO early stops the computation without an error */
+#[cfg(target_os = "linux")]
+#[test]
+fn fifo_output() {
+    use std::io::Read;
+
+    let path = std::env::temp_dir().join(format!("parpi-fifo-{}", std::process::id()));
+    let status = Command::new("mkfifo").arg(&path).status().unwrap();
+    assert!(status.success());
+
+    let mut writer = Command::new(env!("CARGO_BIN_EXE_parpi"))
+        .args(["100K", "--output", path.to_str().unwrap()])
+        .spawn()
+        .unwrap();
+    let mut first = [0; 30];
+    std::fs::File::open(&path)
+        .unwrap()
+        .read_exact(&mut first)
+        .unwrap();
+    assert_eq!(&first[..], parpi::pi_string(28).as_bytes());
+
+    assert!(writer.wait().unwrap().success());
+    std::fs::remove_file(&path).unwrap();
+}
+
+/* the default pool may itself have a single thread, so explicit larger
+ * counts are compared as well */
+#[test]
+fn output_independent_of_thread_count() {
+    let single = parpi(&["500", "--threads", "1"]);
+    assert_eq!(parpi(&["500"]), single);
+    for threads in ["2", "3", "8"] {
+        assert_eq!(
+            parpi(&["500", "--threads", threads]),
+            single,
+            "{} threads",
+            threads
+        );
+        assert_eq!(
+            parpi(&["500", "--threads", threads, "--stream"]),
+            single,
+            "{} threads, streamed",
+            threads
+        );
+    }
+}
+
+/* the warning comes before any digit is computed, so the run can be
+ * stopped as soon as it is seen */
+#[test]
+fn warn_past_accurate_digits() {
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+
+    let out = Command::new(env!("CARGO_BIN_EXE_parpi"))
+        .arg("100")
+        .output()
+        .unwrap();
+    assert!(out.stderr.is_empty());
+
+    let count = (parpi::max_accurate_digits() + 1).to_string();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_parpi"))
+        .arg(&count)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut line = String::new();
+    BufReader::new(child.stderr.take().unwrap())
+        .read_line(&mut line)
+        .unwrap();
+    assert!(line.starts_with("parpi: warning: "), "{:?}", line);
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
+
+/* --exact gives the same digits on stdout and through --output, and
+ * raises the count past which it warns */
+#[test]
+fn exact_sum() {
+    let path = std::env::temp_dir().join(format!("parpi-exact-{}.txt", std::process::id()));
+    let file = path.to_str().unwrap();
+    let expected = parpi(&["500", "--group", "7"]);
+    assert_eq!(parpi(&["500", "--group", "7", "--exact"]), expected);
+    assert_eq!(
+        parpi(&["500", "--group", "7", "--exact", "--output", file]),
+        ""
+    );
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), expected);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(
+        parpi(&["500", "--sum", "--exact"]),
+        parpi(&["500", "--sum"])
+    );
+
+    let count = (parpi::max_accurate_digits() + 1).to_string();
+    let out = Command::new(env!("CARGO_BIN_EXE_parpi"))
+        .args([&count, "--exact", "--time", "0"])
+        .output()
+        .unwrap();
+    assert_eq!(String::from_utf8(out.stdout).unwrap(), "3\n");
+    assert!(out.stderr.is_empty(), "{:?}", out.stderr);
+}
+
+#[test]
+fn benchmark_reports_throughput() {
+    let out = Command::new(env!("CARGO_BIN_EXE_parpi"))
+        .args(["200", "--benchmark", "--threads", "2"])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    assert!(out.stdout.is_empty());
+    let report = String::from_utf8(out.stderr).unwrap();
+    assert!(
+        report.starts_with("200 digits on 2 threads in "),
+        "{}",
+        report
+    );
+    assert!(report.trim_end().ends_with(" digits/s"), "{}", report);
+}
+
+#[test]
+fn check_against_file() {
+    let dir = std::env::temp_dir();
+    let reference = dir.join(format!("parpi-check-{}.txt", std::process::id()));
+    let output = dir.join(format!("parpi-check-out-{}.txt", std::process::id()));
+    let check = |contents: &str, args: &[&str]| {
+        std::fs::write(&reference, contents).unwrap();
+        let out = Command::new(env!("CARGO_BIN_EXE_parpi"))
+            .args(args)
+            .arg("--check")
+            .arg(&reference)
+            .output()
+            .unwrap();
+        (out.status.success(), String::from_utf8(out.stderr).unwrap())
+    };
+
+    let pi = parpi::pi_string(200);
+    let wrapped: String = pi
+        .as_bytes()
+        .chunks(50)
+        .map(|line| String::from_utf8(line.to_vec()).unwrap() + "\n")
+        .collect();
+    let mut wrong = pi.clone().into_bytes();
+    wrong[2 + 57] = if wrong[2 + 57] == b'0' { b'1' } else { b'0' };
+    let wrong = String::from_utf8(wrong).unwrap();
+    let out = output.to_str().unwrap();
+
+    for args in [
+        &["100"][..],
+        &["100", "--group", "7"],
+        &["100", "--output", out],
+    ] {
+        assert_eq!(check(&wrapped, args), (true, String::new()), "{:?}", args);
+        assert_eq!(check(&pi[2..], args), (true, String::new()), "{:?}", args);
+        /* only the digits the file holds are compared */
+        assert_eq!(check(&pi[..52], args), (true, String::new()), "{:?}", args);
+        assert_eq!(
+            check(&wrong, args),
+            (
+                false,
+                "parpi: digit 57 does not match the reference\n".to_string()
+            ),
+            "{:?}",
+            args
+        );
+    }
+    let (ok, err) = check("3.14x", &["10"]);
+    assert!(!ok && err.contains("unexpected 'x'"), "{}", err);
+
+    std::fs::remove_file(&reference).unwrap();
+    std::fs::remove_file(&output).unwrap();
+}
+
+#[test]
+fn color_groups() {
+    assert_eq!(
+        parpi(&["12", "--group", "5", "--color", "always"]),
+        "3.\x1b[36m14159 \x1b[33m26535 \x1b[36m89\x1b[0m\n"
+    );
+    assert_eq!(
+        parpi(&["12", "--color", "always"]),
+        "3.\x1b[36m1415926535\x1b[33m89\x1b[0m\n"
+    );
+    assert_eq!(parpi(&["0", "--color", "always"]), "3\n");
+    /* the output here is a pipe, and JSON is never colored */
+    assert_eq!(parpi(&["12", "--group", "5"]), "3.14159 26535 89\n");
+    assert_eq!(
+        parpi(&["3", "--format", "json", "--color", "always"]),
+        parpi(&["3", "--format", "json"])
+    );
+}
+
+#[test]
+fn several_counts() {
+    assert_eq!(
+        parpi(&["5", "0", "12", "--group", "4"]),
+        "5: 3.1415 9\n0: 3\n12: 3.1415 9265 3589\n"
+    );
+    assert_eq!(parpi(&["3", "2", "--format", "raw"]), "3: 141\n2: 14\n");
+    assert_eq!(
+        parpi(&["1", "2", "--format", "json"]),
+        "{\"digits\":\"3.1\",\"count\":1}\n{\"digits\":\"3.14\",\"count\":2}\n"
+    );
+    for format in ["jsonl", "base64", "rust", "c-header"] {
+        let out = Command::new(env!("CARGO_BIN_EXE_parpi"))
+            .args(["1", "2", "--format", format])
+            .output()
+            .unwrap();
+        assert!(!out.status.success(), "{}", format);
+    }
+}
+
+/* blocks a checkpoint counts as done are left alone, so overwriting them
+ * shows which blocks a resumed run skipped */
+#[test]
+fn resume_from_checkpoint() {
+    let dir = std::env::temp_dir();
+    let output = dir.join(format!("parpi-resume-{}.txt", std::process::id()));
+    let checkpoint = dir.join(format!("parpi-resume-{}.ck", std::process::id()));
+    let run_with = |extra: &[&str]| {
+        let args = [
+            "100",
+            "--group",
+            "10",
+            "--output",
+            output.to_str().unwrap(),
+            "--resume",
+            checkpoint.to_str().unwrap(),
+        ];
+        parpi(&[&args[..], extra].concat())
+    };
+    let run = || run_with(&[]);
+    let expected = parpi(&["100", "--group", "10"]);
+
+    run();
+    assert_eq!(std::fs::read_to_string(&output).unwrap(), expected);
+    let saved = std::fs::read_to_string(&checkpoint).unwrap();
+    let layout = saved.trim_end().strip_suffix(" 12").unwrap();
+    assert!(layout.ends_with(" exact=false"), "{}", layout);
+
+    /* the first five blocks are the 45 digits and four spaces after "3." */
+    let marked = "3.".to_string() + &"x".repeat(49) + &expected[51..];
+    std::fs::write(&output, &marked).unwrap();
+    std::fs::write(&checkpoint, format!("{} 5\n", layout)).unwrap();
+    run();
+    assert_eq!(std::fs::read_to_string(&output).unwrap(), marked);
+    assert_eq!(std::fs::read_to_string(&checkpoint).unwrap(), saved);
+
+    /* a corrupt checkpoint or one from other options starts over */
+    for bad in [
+        "garbage",
+        &format!("{} 5x", layout),
+        "parpi-checkpoint digits=99 5",
+    ] {
+        std::fs::write(&output, &marked).unwrap();
+        std::fs::write(&checkpoint, bad).unwrap();
+        run();
+        assert_eq!(
+            std::fs::read_to_string(&output).unwrap(),
+            expected,
+            "{}",
+            bad
+        );
+    }
+
+    /* nor do the f64 blocks of a checkpoint count for an --exact run */
+    std::fs::write(&output, &marked).unwrap();
+    std::fs::write(&checkpoint, format!("{} 5\n", layout)).unwrap();
+    run_with(&["--exact"]);
+    assert_eq!(std::fs::read_to_string(&output).unwrap(), expected);
+
+    std::fs::remove_file(&output).unwrap();
+    std::fs::remove_file(&checkpoint).unwrap();
+}
+
+#[test]
+fn output_independent_of_chunk_size() {
+    let path = std::env::temp_dir().join(format!("parpi-chunks-{}.txt", std::process::id()));
+    let file = path.to_str().unwrap();
+    let expected = parpi(&["300"]);
+    for chunk in ["1", "3", "7", "100"] {
+        let args = ["300", "--threads", "3", "--chunk-size", chunk];
+        assert_eq!(parpi(&args), expected, "chunks of {}", chunk);
+        assert_eq!(
+            parpi(&[&args[..], &["--stream"]].concat()),
+            expected,
+            "chunks of {}, streamed",
+            chunk
+        );
+        assert_eq!(
+            parpi(&[&args[..], &["--window", "2"]].concat()),
+            expected,
+            "chunks of {}, window of 2",
+            chunk
+        );
+        parpi(&[&args[..], &["--output", file]].concat());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), expected);
+    }
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn hex_and_binary_output() {
+    assert_eq!(parpi(&["20", "--base", "16"]), "3.243F6A8885A308D31319\n");
+    assert_eq!(parpi(&["10", "--base", "2"]), "11.0010010000\n");
+    assert_eq!(parpi(&["0", "--base", "2"]), "11\n");
+    assert_eq!(
+        parpi(&["8", "--base", "16", "--format", "raw", "--group", "4"]),
+        "243F 6A88\n"
+    );
+
+    /* every hex digit is four bits of the binary expansion */
+    let hex = parpi(&["50", "--base", "16", "--format", "raw"]);
+    let binary = parpi(&["200", "--base", "2", "--format", "raw"]);
+    let bits: String = hex
+        .trim_end()
+        .chars()
+        .map(|c| format!("{:04b}", c.to_digit(16).unwrap()))
+        .collect();
+    assert_eq!(binary.trim_end(), bits);
+
+    let out = Command::new(env!("CARGO_BIN_EXE_parpi"))
+        .args(["10", "--base", "7"])
+        .output()
+        .unwrap();
+    assert!(!out.status.success());
+    assert!(String::from_utf8(out.stderr)
+        .unwrap()
+        .contains("base 7 is not supported"));
+}
+
+/* run parpi with `input` on stdin */
+fn parpi_with_stdin(args: &[&str], input: &[u8]) -> std::process::Output {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_parpi"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(input).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+/* "-" reads the count from a line of stdin, which is checked like a count
+ * on the command line */
+#[test]
+fn count_from_stdin() {
+    let out = parpi_with_stdin(&["-"], b"20\n");
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8(out.stdout).unwrap(), parpi(&["20"]));
+
+    let out = parpi_with_stdin(&["-"], b"1x\n");
+    assert!(!out.status.success());
+    assert!(out.stdout.is_empty());
+    assert!(String::from_utf8(out.stderr)
+        .unwrap()
+        .contains("invalid digit count '1x'"));
+}
+
+#[test]
+fn repl_queries() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_parpi"))
+        .args(["repl", "--threads", "2"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"0\n0-9\n\n 1000 - 1010 \n5-12\n9-3\nfoo\n1K\n97094898\n")
+        .unwrap();
+    let out = child.wait_with_output().unwrap();
+    assert!(out.status.success());
+    let pi = parpi::pi_string(1011);
+    let digits = &pi[2..];
+    let lines: Vec<String> = String::from_utf8(out.stdout)
+        .unwrap()
+        .lines()
+        .map(String::from)
+        .collect();
+    assert_eq!(
+        lines[..4],
+        ["1", "1415926535", &digits[1000..], &digits[5..13]]
+    );
+    assert!(lines[4].starts_with("error: ") && lines[5].starts_with("error: "));
+    assert_eq!(lines[6], &digits[1000..1001]);
+    assert_eq!(
+        lines[7],
+        "error: digit count 97094898 is too large, at most 97094898 digits are supported"
+    );
+    assert_eq!(lines.len(), 8);
+}
+
+#[test]
+fn digit_sum() {
+    assert_eq!(parpi(&["10", "--sum"]), "41\n");
+    assert_eq!(parpi(&["0", "4", "--sum"]), "0: 0\n4: 11\n");
+    let out = Command::new(env!("CARGO_BIN_EXE_parpi"))
+        .args(["10", "--sum", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(!out.status.success());
+}
+
+/* the digest of "1415926535", as printed by `printf 1415926535 | sha256sum` */
+#[test]
+fn sha256_of_raw_digits() {
+    let digest = "66181a051b887271f036c64620bce3225af7d38f9e3f4a16dfc55de7d351f44b";
+    assert_eq!(parpi(&["10", "--sha256"]), format!("{}\n", digest));
+    assert_eq!(
+        parpi(&["10", "--sha256", "--verify"]),
+        format!("{}\n", digest)
+    );
+    assert_eq!(
+        parpi(&["10", "5", "--sha256"]).lines().next().unwrap(),
+        format!("10: {}", digest)
+    );
+}
+
+/* a budget far too short for the count prints the whole blocks done in
+ * time, and a JSON count that matches them */
+#[test]
+fn time_budget() {
+    let out = parpi(&["1M", "--time", "0.3", "--format", "raw"]);
+    let digits = out.trim_end();
+    assert!(digits.len() < 1_000_000);
+    assert_eq!(digits.len() % 9, 0);
+    /* on a loaded machine no block may be done in time, when pi_string
+     * gives just "3" */
+    let expected = parpi::pi_string(digits.len() as u32);
+    assert_eq!(digits, expected.get(2..).unwrap_or(""));
+
+    let out = parpi(&["1M", "--time", "0.3", "--format", "json"]);
+    let result: serde_json::Value = serde_json::from_str(&out).unwrap();
+    let count = result["count"].as_u64().unwrap() as u32;
+    let prefix = parpi::pi_prefix(count);
+    assert_eq!(
+        result["digits"].as_str().unwrap().len(),
+        prefix.len() + count as usize
+    );
+
+    assert_eq!(parpi(&["20", "--time", "60"]), "3.14159265358979323846\n");
+}
+
+/* with no time at all not a single block is done, and the output is
+ * framed as the zero digits it holds, labelled with the counts asked for */
+#[test]
+fn time_budget_zero() {
+    assert_eq!(parpi(&["1M", "--time", "0"]), "3\n");
+    assert_eq!(parpi(&["1M", "--time", "0", "--format", "raw"]), "\n");
+    assert_eq!(parpi(&["10", "1M", "--time", "0"]), "10: 3\n1000000: 3\n");
+    assert_eq!(
+        parpi(&["1M", "--time", "0", "--format", "json"]),
+        "{\"digits\":\"3\",\"count\":0}\n"
+    );
+    assert_eq!(
+        parpi(&["1M", "--time", "0", "--format", "rust"]),
+        "const DIGITS: &[u8] = b\"3\";\n// 0 digits after the decimal point\n"
+    );
+}
+
+/* the first Ctrl-C still writes out the whole blocks done so far */
+#[cfg(unix)]
+#[test]
+fn interrupt_writes_digits_done() {
+    use std::process::Stdio;
+
+    let child = Command::new(env!("CARGO_BIN_EXE_parpi"))
+        .args(["1M", "--format", "raw"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    let kill = Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()
+        .unwrap();
+    assert!(kill.success());
+
+    let out = child.wait_with_output().unwrap();
+    assert_eq!(out.status.code(), Some(130));
+    let out = String::from_utf8(out.stdout).unwrap();
+    let digits = out.strip_suffix('\n').unwrap();
+    assert_eq!(digits.len() % 9, 0);
+    /* on a loaded machine no block may be done in time, when pi_string
+     * gives just "3" */
+    let expected = parpi::pi_string(digits.len() as u32);
+    assert_eq!(digits, expected.get(2..).unwrap_or(""));
+}
+
+/* Ctrl-C stops --sum and --sha256 too, without printing the sum or hash
+ * of only the digits done */
+#[cfg(unix)]
+#[test]
+fn interrupt_stops_sum_and_hash() {
+    use std::process::Stdio;
+
+    for flag in ["--sum", "--sha256"] {
+        let child = Command::new(env!("CARGO_BIN_EXE_parpi"))
+            .args(["1M", flag])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(500));
+        let kill = Command::new("kill")
+            .args(["-INT", &child.id().to_string()])
+            .status()
+            .unwrap();
+        assert!(kill.success());
+
+        let out = child.wait_with_output().unwrap();
+        assert_eq!(out.status.code(), Some(130), "{}", flag);
+        assert!(out.stdout.is_empty(), "{}", flag);
+        let stderr = String::from_utf8(out.stderr).unwrap();
+        assert!(stderr.ends_with("parpi: interrupted\n"), "{:?}", stderr);
+    }
+}
+
+#[test]
+fn stats_on_stderr() {
+    let out = Command::new(env!("CARGO_BIN_EXE_parpi"))
+        .args(["1000", "--stats"])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8(out.stdout).unwrap(), parpi(&["1000"]));
+    assert_eq!(
+        String::from_utf8(out.stderr).unwrap(),
+        concat!(
+            "blocks: 112\n",
+            "last block: digits 1000 to 1008\n",
+            "terms: 906\n",
+            "primes: 396 up to 2718\n",
+            "largest modulus: 3^7\n",
+            "accurate digits: 1000 of 1000 (estimated)\n",
+        )
+    );
+}
+
+/* options on the command line and in the environment win over the file;
+ * the thread count shows in the --benchmark report */
+#[test]
+fn config_file_defaults() {
+    let dir = std::env::temp_dir().join(format!("parpi-config-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("parpi.toml"), "threads = 3\nformat = \"raw\"\n").unwrap();
+    let run = |args: &[&str], env: &[(&str, &str)]| {
+        let out = Command::new(env!("CARGO_BIN_EXE_parpi"))
+            .args(args)
+            .env("XDG_CONFIG_HOME", &dir)
+            .env_remove("PARPI_THREADS")
+            .envs(env.iter().copied())
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8(out.stdout).unwrap();
+        let stderr = String::from_utf8(out.stderr).unwrap();
+        (out.status.success(), stdout, stderr)
+    };
+
+    assert_eq!(run(&["5"], &[]).1, "14159\n");
+    assert_eq!(run(&["5", "--format", "decimal"], &[]).1, "3.14159\n");
+    let threads = |args: &[&str], env| {
+        let (_, _, report) = run(&[args, &["--benchmark"]].concat(), env);
+        report.split(" on ").nth(1).unwrap().to_string()
+    };
+    assert!(threads(&["5"], &[]).starts_with("3 threads"));
+    assert!(threads(&["5"], &[("PARPI_THREADS", "2")]).starts_with("2 threads"));
+    assert!(threads(&["5", "--threads", "1"], &[]).starts_with("1 thread "));
+
+    let other = dir.join("other.toml");
+    std::fs::write(&other, "formt = \"raw\"\n").unwrap();
+    let (ok, _, err) = run(&["5", "--config", other.to_str().unwrap()], &[]);
+    assert!(!ok && err.contains("formt"), "{}", err);
+    let missing = dir.join("missing.toml");
+    assert!(!run(&["5", "--config", missing.to_str().unwrap()], &[]).0);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn compressed_output_round_trips() {
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    let path = std::env::temp_dir().join(format!("parpi-gzip-{}.gz", std::process::id()));
+    let file = path.to_str().unwrap();
+    for args in [&["1000", "--format", "raw"][..], &["100", "--group", "10"]] {
+        parpi(&[args, &["--compress", "--output", file]].concat());
+        let mut digits = String::new();
+        GzDecoder::new(std::fs::File::open(&path).unwrap())
+            .read_to_string(&mut digits)
+            .unwrap();
+        assert_eq!(digits, parpi(args), "{:?}", args);
+    }
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,25 @@
+#![cfg(feature = "ffi")]
+
+use parpi::ffi::{parpi_digit_at, parpi_fill};
+use std::ptr;
+
+#[test]
+fn fill_round_trip() {
+    let mut buf = vec![0u8; 101];
+    let n = unsafe { parpi_fill(buf.as_mut_ptr(), buf.len()) };
+    assert_eq!(n, 101);
+    assert_eq!(buf, parpi::pi_string(100).replace('.', "").as_bytes());
+    for (i, &d) in buf[1..].iter().enumerate() {
+        assert_eq!(parpi_digit_at(i as u32), d - b'0');
+    }
+}
+
+#[test]
+fn rejected_arguments() {
+    let mut buf = [b'x'; 4];
+    assert_eq!(unsafe { parpi_fill(ptr::null_mut(), 10) }, 0);
+    assert_eq!(unsafe { parpi_fill(buf.as_mut_ptr(), 0) }, 0);
+    assert_eq!(buf, [b'x'; 4]);
+    assert_eq!(parpi_digit_at(parpi::MAX_DIGITS), 255);
+    assert_eq!(parpi_digit_at(u32::MAX), 255);
+}
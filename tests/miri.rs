@@ -0,0 +1,46 @@
+/*
+ * A computation small enough to run under Miri, which checks the integer
+ * and float conversions, the shifts in divn! and inv_mod2, and the raw
+ * buffer handed to the FFI for undefined behaviour and overflow:
+ *
+ *     cargo +nightly miri test --no-default-features --features std,ffi \
+ *         --test miri
+ *
+ * Without rayon the work runs serially on the calling thread; with it Miri
+ * stops early at a Stacked Borrows report inside crossbeam-epoch, which
+ * rayon's scheduler uses. The other tests run far too long under the
+ * interpreter. These also run as ordinary tests.
+ */
+
+use parpi::modmath::{inv_mod, inv_mod2, max_power, mul_mod, pow_mod};
+
+#[test]
+fn first_blocks() {
+    assert_eq!(parpi::calc_digits(1), 141_592_653);
+    assert_eq!(parpi::calc_digits(10), 589_793_238);
+    assert_eq!(parpi::calc_digits_with::<i64>(1), 141_592_653);
+    assert_eq!(parpi::pi_string(20), "3.14159265358979323846");
+}
+
+#[test]
+fn arithmetic_at_the_limits() {
+    assert_eq!(mul_mod(i32::MAX - 1, i32::MAX - 2, i32::MAX), 2);
+    assert_eq!(mul_mod(i32::MIN, i32::MIN, i32::MAX), 1);
+    assert_eq!(mul_mod(i64::MAX - 1, i64::MAX - 2, i64::MAX), 2);
+    assert_eq!(pow_mod(2, i32::MAX, i32::MAX), 2);
+    assert_eq!(max_power(2, i32::MAX), 30);
+
+    let v = (1 << 30) - 35;
+    let inv = inv_mod2(v - 1, v);
+    assert_eq!(inv, inv_mod(v - 1, v));
+    assert_eq!(mul_mod(v - 1, inv, v), 1);
+}
+
+#[cfg(feature = "ffi")]
+#[test]
+fn fill_through_ffi() {
+    let mut buf = vec![0u8; 21];
+    let n = unsafe { parpi::ffi::parpi_fill(buf.as_mut_ptr(), buf.len()) };
+    assert_eq!(n, 21);
+    assert_eq!(buf, b"314159265358979323846");
+}
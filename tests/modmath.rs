@@ -0,0 +1,229 @@
+use parpi::modmath::{
+    inv_mod, inv_mod2, is_prime, max_power, mul_mod, mul_mod128, next_prime, pow_mod, sieve,
+};
+
+/* negative bases keep the sign of the power, as mul_mod does, and large
+ * moduli catch products reduced too late */
+#[test]
+fn pow_mod_matches_naive_loop() {
+    let large = [243, 65_537, (1 << 30) - 35, i32::MAX];
+    for m in (1..40).chain(large) {
+        let bases = (-40..40).chain([m - 1, m / 2 + 1, i32::MAX - 2]);
+        for a in bases {
+            assert_eq!(pow_mod(a, 0, m), 1, "pow_mod({}, 0, {})", a, m);
+            let mut naive = 1 % m as i64;
+            for b in 1..64 {
+                naive = naive * a as i64 % m as i64;
+                assert_eq!(
+                    pow_mod(a, b, m) as i64,
+                    naive,
+                    "pow_mod({}, {}, {})",
+                    a,
+                    b,
+                    m
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn is_prime_small_values() {
+    assert!(is_prime(2));
+    assert!(is_prime(3));
+    assert!(!is_prime(4));
+    assert!(!is_prime(9));
+}
+
+#[test]
+fn is_prime_below_two() {
+    assert!(!is_prime(0));
+    assert!(!is_prime(1));
+    assert!(!is_prime(-1));
+    assert!(!is_prime(-2));
+    assert!(!is_prime(-7));
+    assert!(!is_prime(i32::MIN));
+}
+
+#[test]
+fn is_prime_near_i32_max() {
+    assert!(is_prime(i32::MAX));
+    assert!(is_prime(2_147_483_629));
+    assert!(!is_prime(46_327 * 46_337));
+    assert!(!is_prime(46_337 * 46_337));
+}
+
+/*
+ * calc_digits walks a prime list sieved once and shared by all blocks. For
+ * 5000 digits, release build on one core, the run took 111.7s with the
+ * per-block next_prime walk and 119.8s with the shared list (which also
+ * computes one more block, as exactly 5000 digits are now printed): the
+ * prime enumeration was never a significant part of the cost.
+ */
+#[test]
+fn sieve_matches_next_prime() {
+    let primes = sieve(100_000);
+    let mut p = 1;
+    for &q in &primes {
+        p = next_prime(p);
+        assert_eq!(q, p);
+    }
+    assert!(next_prime(p) > 100_000);
+}
+
+#[test]
+fn mul_mod_near_2_pow_31() {
+    let a = i32::MAX - 1;
+    let b = i32::MAX - 2;
+    let n = i32::MAX;
+    let expected = ((a as i128 * b as i128) % n as i128) as i32;
+    assert_eq!(mul_mod(a, b, n), expected);
+    assert_eq!(mul_mod(a, b, n), 2);
+    assert_eq!(mul_mod128(a as i64, b as i64, n as i64), 2);
+}
+
+#[test]
+fn mul_mod128_near_2_pow_63() {
+    let a = i64::MAX - 1;
+    let b = i64::MAX - 2;
+    let n = i64::MAX;
+    assert_eq!(mul_mod128(a, b, n), 2);
+    assert_eq!(
+        mul_mod128(a, b, 1_000_000_007),
+        ((a as i128 * b as i128) % 1_000_000_007) as i64
+    );
+}
+
+/* the float formula calc_digits used before misses exact powers, e.g. it
+ * gives 4 for 3^5 = 243 */
+#[test]
+fn max_power_matches_float_log() {
+    for limit in (2..5000).chain([243, 4913, 29791, 59049, 68921]) {
+        for &a in sieve(1000).iter().take_while(|&&a| a <= limit) {
+            let v = max_power(a, limit);
+            let power = (a as i64).pow(v as u32);
+            assert!(power <= limit as i64 && power * a as i64 > limit as i64);
+
+            let float = ((limit as f64).ln() / (a as f64).ln()) as i32;
+            assert!(v == float || (v == float + 1 && power == limit as i64));
+        }
+    }
+}
+
+fn gcd(a: i32, b: i32) -> i32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/* calc_digits uses inv_mod for powers of 2 and inv_mod2 for the odd prime
+ * powers, so on odd moduli the two must give the same inverse */
+#[test]
+fn inv_mod_agrees_with_inv_mod2() {
+    let small = (3..400).step_by(2);
+    /* prime powers like those calc_digits reduces by, up to just below 2^30 */
+    let powers = [3i32.pow(18), 5i32.pow(12), 7i32.pow(10), 59_049, 1_046_527];
+    for v in small.chain(powers.iter().copied()).chain([(1 << 30) - 35]) {
+        let us: Vec<i32> = if v < 400 {
+            (1..v).collect()
+        } else {
+            (1..200).chain((v - 200..v).step_by(7)).collect()
+        };
+        for u in us.into_iter().filter(|&u| gcd(u, v) == 1) {
+            let inv = inv_mod(u, v);
+            assert_eq!(inv_mod2(u, v), inv, "inverse of {} modulo {}", u, v);
+            assert!((0..v).contains(&inv));
+            assert_eq!(mul_mod(u, inv, v), 1, "inverse of {} modulo {}", u, v);
+        }
+    }
+}
+
+/* on values that fit in an i32 the i64 arithmetic gives the same results,
+ * and past 2^31 it still matches the i128 product */
+#[test]
+fn i64_matches_i32() {
+    let values = [0, 1, 2, 3, 7, 1000, 65_535, 1 << 29, i32::MAX - 1];
+    let moduli = [3, 243, 65_537, 59_049, (1 << 30) - 35];
+    for &n in &moduli {
+        for &a in &values {
+            for &b in &values {
+                let r = mul_mod(a, b, n);
+                assert_eq!(mul_mod(a as i64, b as i64, n as i64), r as i64);
+            }
+            for e in [0, 1, 2, 31, 1000, i32::MAX] {
+                assert_eq!(pow_mod(a as i64, e, n as i64), pow_mod(a, e, n) as i64);
+            }
+            if a > 0 && gcd(a % n, n) == 1 {
+                let inv = inv_mod(a, n) as i64;
+                assert_eq!(inv_mod(a as i64, n as i64), inv);
+                assert_eq!(inv_mod2(a as i64 % n as i64, n as i64), inv);
+            }
+        }
+    }
+
+    let n = (1i64 << 61) - 1;
+    let a = (1i64 << 60) + 12_345;
+    let naive = (1..=20).fold(1i128, |r, _| r * a as i128 % n as i128) as i64;
+    assert_eq!(pow_mod(a, 20, n), naive);
+    let inv = inv_mod2(a, n);
+    assert_eq!(inv_mod(a, n), inv);
+    assert_eq!(mul_mod(a, inv, n), 1);
+}
+
+mod properties {
+    use parpi::modmath::mul_mod;
+    use proptest::prelude::*;
+
+    proptest! {
+        /* the remainder of the exact product, with the sign of the product
+         * as i128's % gives it */
+        #[test]
+        fn mul_mod_i32_is_exact_remainder(a: i32, b: i32, n in 1..=i32::MAX) {
+            let expected = (a as i128 * b as i128) % n as i128;
+            let r = mul_mod(a, b, n);
+            prop_assert_eq!(r as i128, expected);
+            prop_assert!(r.abs() < n);
+            prop_assert!(r == 0 || (r < 0) == ((a < 0) != (b < 0)));
+        }
+
+        #[test]
+        fn mul_mod_i64_is_exact_remainder(a: i64, b: i64, n in 1..=i64::MAX) {
+            let expected = (a as i128 * b as i128) % n as i128;
+            let r = mul_mod(a, b, n);
+            prop_assert_eq!(r as i128, expected);
+            prop_assert!(r.abs() < n);
+            prop_assert!(r == 0 || (r < 0) == ((a < 0) != (b < 0)));
+        }
+    }
+
+    #[test]
+    fn mul_mod_negative_operands() {
+        assert_eq!(mul_mod(-3, 5, 7), -1);
+        assert_eq!(mul_mod(3, -5, 7), -1);
+        assert_eq!(mul_mod(-3, -5, 7), 1);
+        assert_eq!(mul_mod(i32::MIN, i32::MIN, i32::MAX), 1);
+        assert_eq!(mul_mod(i32::MIN, 1, i32::MAX), -1);
+    }
+}
+
+/* the pow_mod calls the sum for position n makes, now and before the 2^n
+ * and 5^(n-1) factors of the odd primes were folded into one: every prime
+ * up to 3 * num_terms(n) calls it, except 2 once its modulus is 2^0 */
+fn pow_mod_calls(n: i32) -> (usize, usize) {
+    let nl = parpi::num_terms(n);
+    let primes = sieve(3 * nl);
+    let two = (max_power(2, 3 * nl) + nl - n > 0) as usize;
+    let odd = primes.len() - 1;
+    (odd + two, 2 * odd + two)
+}
+
+/* the positions benches/calc.rs times make about half the pow_mod calls
+ * they made before */
+#[test]
+fn pow_mod_calls_per_position() {
+    for (n, now, before) in [(1, 17, 33), (1_000, 395, 790), (10_000, 2917, 5834)] {
+        assert_eq!(pow_mod_calls(n), (now, before), "position {}", n);
+    }
+}
@@ -0,0 +1,73 @@
+use parpi::{parse_count, ParpiError, MAX_DIGITS};
+
+/* every input parses to a computable count that prints back to itself,
+ * or fails with one of the two count errors naming the input */
+fn check(s: &str) -> Result<u32, ParpiError> {
+    let result = parse_count(s);
+    match &result {
+        Ok(n) => {
+            assert!(*n <= MAX_DIGITS, "{:?} gave {}", s, n);
+            assert_eq!(parse_count(&n.to_string()), Ok(*n));
+        }
+        Err(ParpiError::InvalidCount(t)) | Err(ParpiError::CountTooLarge(t)) => assert_eq!(t, s),
+        Err(e) => panic!("{:?} gave unexpected error {:?}", s, e),
+    }
+    result
+}
+
+#[test]
+fn adversarial_inputs() {
+    let invalid = [
+        "", " ", "10 ", " 10", "\t10", "10\n", "-1", "+1", "1.5", "1e3", "0x10", "K", "M", "_",
+        "1__", "_1", "1_", "1_K", "1KK", "1MB", "1G", "١٢", "１０", "10\0",
+    ];
+    for s in invalid {
+        assert!(
+            matches!(check(s), Err(ParpiError::InvalidCount(_))),
+            "{:?}",
+            s
+        );
+    }
+
+    let too_large = [
+        "97094899",
+        "4294967295",
+        "4294967296",
+        "99999999999999999999999999999",
+        "98M",
+        "4295M",
+        "5000000K",
+    ];
+    for s in too_large {
+        assert!(
+            matches!(check(s), Err(ParpiError::CountTooLarge(_))),
+            "{:?}",
+            s
+        );
+    }
+
+    assert_eq!(check("0"), Ok(0));
+    assert_eq!(check("000"), Ok(0));
+    assert_eq!(check("1_000"), Ok(1000));
+    assert_eq!(check("10k"), Ok(10_000));
+    assert_eq!(check("97M"), Ok(97_000_000));
+    assert_eq!(check("97094898"), Ok(MAX_DIGITS));
+}
+
+/* strings built from the characters the parser cares about, plus a few it
+ * must reject, from a fixed xorshift sequence */
+#[test]
+fn random_inputs() {
+    let alphabet: Vec<char> = "0123456789_kKmM .-+\u{0}é９".chars().collect();
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    for _ in 0..100_000 {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        let len = (state % 14) as usize;
+        let s: String = (0..len)
+            .map(|i| alphabet[(state >> (4 * i)) as usize % alphabet.len()])
+            .collect();
+        let _ = check(&s);
+    }
+}
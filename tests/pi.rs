@@ -0,0 +1,494 @@
+use parpi::pi_string;
+
+/* the first 2000 digits after the decimal point */
+const PI_2000: &str = concat!(
+    "14159265358979323846264338327950288419716939937510",
+    "58209749445923078164062862089986280348253421170679",
+    "82148086513282306647093844609550582231725359408128",
+    "48111745028410270193852110555964462294895493038196",
+    "44288109756659334461284756482337867831652712019091",
+    "45648566923460348610454326648213393607260249141273",
+    "72458700660631558817488152092096282925409171536436",
+    "78925903600113305305488204665213841469519415116094",
+    "33057270365759591953092186117381932611793105118548",
+    "07446237996274956735188575272489122793818301194912",
+    "98336733624406566430860213949463952247371907021798",
+    "60943702770539217176293176752384674818467669405132",
+    "00056812714526356082778577134275778960917363717872",
+    "14684409012249534301465495853710507922796892589235",
+    "42019956112129021960864034418159813629774771309960",
+    "51870721134999999837297804995105973173281609631859",
+    "50244594553469083026425223082533446850352619311881",
+    "71010003137838752886587533208381420617177669147303",
+    "59825349042875546873115956286388235378759375195778",
+    "18577805321712268066130019278766111959092164201989",
+    "38095257201065485863278865936153381827968230301952",
+    "03530185296899577362259941389124972177528347913151",
+    "55748572424541506959508295331168617278558890750983",
+    "81754637464939319255060400927701671139009848824012",
+    "85836160356370766010471018194295559619894676783744",
+    "94482553797747268471040475346462080466842590694912",
+    "93313677028989152104752162056966024058038150193511",
+    "25338243003558764024749647326391419927260426992279",
+    "67823547816360093417216412199245863150302861829745",
+    "55706749838505494588586926995690927210797509302955",
+    "32116534498720275596023648066549911988183479775356",
+    "63698074265425278625518184175746728909777727938000",
+    "81647060016145249192173217214772350141441973568548",
+    "16136115735255213347574184946843852332390739414333",
+    "45477624168625189835694855620992192221842725502542",
+    "56887671790494601653466804988627232791786085784383",
+    "82796797668145410095388378636095068006422512520511",
+    "73929848960841284886269456042419652850222106611863",
+    "06744278622039194945047123713786960956364371917287",
+    "46776465757396241389086583264599581339047802759009",
+);
+
+#[test]
+fn pi_string_exact_length() {
+    assert_eq!(pi_string(10).len(), 12);
+    assert_eq!(pi_string(10), "3.1415926535");
+}
+
+#[test]
+fn serial_matches_parallel() {
+    let serial: String = parpi::PiDigitStream::new()
+        .take(501)
+        .map(|d| (b'0' + d) as char)
+        .collect();
+    assert_eq!(pi_string(500).replace('.', ""), serial);
+}
+
+#[test]
+fn first_2000_digits() {
+    assert_eq!(&pi_string(2000)[2..], PI_2000);
+}
+
+/* --verify trusts the built-in reference, so it must agree with the
+ * independent constant above */
+#[test]
+fn reference_digits_match() {
+    assert_eq!(parpi::REFERENCE_DIGITS, &PI_2000[..1000]);
+}
+
+#[test]
+fn zero_digits() {
+    assert_eq!(pi_string(0), "3");
+}
+
+/* blocks followed by digits starting with 00 or 99, where the value of the
+ * block lies close to a digit boundary */
+#[test]
+fn blocks_next_to_digit_boundaries() {
+    for &b in &[33, 84, 94, 117, 219] {
+        let expected: u32 = PI_2000[9 * b..9 * b + 9].parse().unwrap();
+        assert_eq!(parpi::compute_range(b as i32, b as i32 + 1), [expected]);
+    }
+}
+
+/* the last block of a request is computed like any other and cut short,
+ * so the final digits are right without computing a block past them;
+ * this includes requests ending in the blocks next to digit boundaries */
+#[test]
+fn last_digits_at_every_size() {
+    let boundaries = [33, 84, 94, 117].iter().flat_map(|b| {
+        let end = 9 * (b + 1);
+        [end - 1, end, end + 1]
+    });
+    for digits in (1..=40).chain(boundaries).chain([1001]) {
+        let s = pi_string(digits as u32);
+        assert_eq!(&s[2..], &PI_2000[..digits], "{} digits", digits);
+    }
+}
+
+#[test]
+fn max_digits_is_the_overflow_boundary() {
+    use parpi::{check_count, ParpiError, MAX_DIGITS};
+
+    assert_eq!(check_count(MAX_DIGITS), Ok(MAX_DIGITS));
+    assert!(matches!(
+        check_count(MAX_DIGITS + 1),
+        Err(ParpiError::CountTooLarge(_))
+    ));
+    assert!(matches!(
+        check_count(u32::MAX),
+        Err(ParpiError::CountTooLarge(_))
+    ));
+}
+
+/* a negative or inverted range holds no blocks, however far apart its
+ * ends are */
+#[test]
+fn negative_or_inverted_range() {
+    for (start, end) in [(i32::MIN, 1), (-3, 2), (5, 2), (i32::MAX, i32::MIN)] {
+        assert!(
+            parpi::compute_range(start, end).is_empty(),
+            "{}..{}",
+            start,
+            end
+        );
+        let computer = parpi::PiComputer::new().build();
+        assert!(computer.compute_range(start, end).unwrap().is_empty());
+    }
+}
+
+/* counts past MAX_DIGITS are rejected before any block is started, instead
+ * of wrapping around the i32 positions */
+#[test]
+#[should_panic(expected = "beyond MAX_DIGITS")]
+fn compute_range_past_max_digits() {
+    parpi::compute_range(0, (parpi::MAX_DIGITS / 9 + 1) as i32);
+}
+
+#[test]
+#[should_panic(expected = "beyond MAX_DIGITS")]
+fn pi_string_past_max_digits() {
+    parpi::pi_string(parpi::MAX_DIGITS + 1);
+}
+
+/* in a release build 3 * num_terms(n) used to wrap for these, which gave
+ * digit 0 in about a microsecond */
+#[test]
+fn positions_past_max_digits_panic() {
+    use parpi::{calc_digits, pi_digit_at, MAX_DIGITS};
+    use std::panic::catch_unwind;
+
+    let last = MAX_DIGITS as i32 - 8;
+    for n in [last + 1, 900_000_000, 1_500_000_000, i32::MAX, 0, -5] {
+        assert!(catch_unwind(|| calc_digits(n)).is_err(), "position {}", n);
+    }
+    for index in [MAX_DIGITS, 900_000_000, 1_500_000_000, u32::MAX] {
+        assert!(
+            catch_unwind(|| pi_digit_at(index)).is_err(),
+            "index {}",
+            index
+        );
+    }
+}
+
+#[test]
+fn tiny_positions() {
+    assert_eq!(pi_string(1), "3.1");
+    for n in 1..=30 {
+        let expected: u64 = PI_2000[n - 1..n + 8].parse().unwrap();
+        assert_eq!(parpi::calc_digits(n as i32), expected, "position {}", n);
+    }
+}
+
+/* the modular arithmetic in i64 gives the same digits as in i32 */
+#[test]
+fn i64_modulus_matches_i32() {
+    for n in (1..=30).chain([100, 1000, 1999]) {
+        assert_eq!(
+            parpi::calc_digits_with::<i64>(n),
+            parpi::calc_digits(n),
+            "position {}",
+            n
+        );
+    }
+    let expected: u64 = PI_2000[999..1008].parse().unwrap();
+    assert_eq!(parpi::calc_digits_with::<i64>(1000), expected);
+}
+
+/* the exact sum gives the known digits, also in the blocks next to digit
+ * boundaries, and through a PiComputer */
+#[cfg(feature = "bignum")]
+#[test]
+fn exact_sum_matches_known_digits() {
+    for b in (0..20).chain([33, 84, 94, 117, 219]) {
+        let expected: u64 = PI_2000[9 * b..9 * b + 9].parse().unwrap();
+        assert_eq!(
+            parpi::calc_digits_exact(9 * b as i32 + 1),
+            expected,
+            "block {}",
+            b
+        );
+    }
+    let pi = parpi::PiComputer::new()
+        .digits(1000)
+        .prefix(false)
+        .exact(true)
+        .build();
+    assert_eq!(pi.compute().unwrap(), &PI_2000[..1000]);
+    assert_eq!(
+        pi.compute_range(33, 35).unwrap(),
+        parpi::compute_range(33, 35)
+    );
+}
+
+/* a single block on a pool with several threads sums its primes in
+ * parallel, which must give the same digits as the serial sum */
+#[cfg(feature = "rayon")]
+#[test]
+fn split_primes_match_serial_sum() {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(4)
+        .build()
+        .unwrap();
+    for b in (0..20).chain(vec![33, 84, 94, 117, 219]) {
+        let serial = parpi::calc_digits(9 * b + 1) as u32;
+        let split = pool.install(|| parpi::compute_range(b, b + 1));
+        assert_eq!(split, [serial], "block {}", b);
+        assert_eq!(
+            pool.install(|| parpi::pi_digit_at(9 * b as u32)),
+            (serial / 100_000_000) as u8
+        );
+    }
+}
+
+/* every way of computing digits through a PiComputer gives the same
+ * digits whatever the chunk size, including chunks longer than the run */
+#[test]
+fn computer_independent_of_chunk_size() {
+    for chunk in [0, 1, 4, 100] {
+        let pi = parpi::PiComputer::new()
+            .digits(200)
+            .threads(3)
+            .chunk_size(chunk)
+            .build();
+        assert_eq!(pi.compute().unwrap(), pi_string(200), "chunks of {}", chunk);
+        let mut out = Vec::new();
+        pi.write_to(&mut out).unwrap();
+        assert_eq!(out, pi_string(200).as_bytes());
+        assert_eq!(pi.digit_sum().unwrap(), parpi::digit_sum(200));
+        assert_eq!(
+            pi.compute_range(5, 20).unwrap(),
+            parpi::compute_range(5, 20)
+        );
+    }
+    let past = parpi::PiComputer::new()
+        .digits(parpi::MAX_DIGITS + 1)
+        .build();
+    assert!(past.compute().is_err() && past.digit_sum().is_err());
+    let blocks = (parpi::MAX_DIGITS / 9 + 1) as i32;
+    assert!(past.compute_range(0, blocks).is_err());
+}
+
+/* in chunks of several blocks, the first block of each task is done long
+ * before the task ahead of it reaches its last one, so blocks reach the
+ * reorder buffer out of order; whatever the window, the digits come out
+ * in order */
+#[cfg(feature = "rayon")]
+#[test]
+fn streamed_digits_independent_of_window() {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(4)
+        .build()
+        .unwrap();
+    for window in [1, 2, 5, 64] {
+        for chunk in [1, 3] {
+            let pi = parpi::PiComputer::new()
+                .digits(300)
+                .chunk_size(chunk)
+                .window(window)
+                .build();
+            let mut out = Vec::new();
+            pool.install(|| pi.write_to(&mut out)).unwrap();
+            assert_eq!(
+                String::from_utf8(out).unwrap(),
+                pi_string(300),
+                "window of {}, chunks of {}",
+                window,
+                chunk
+            );
+        }
+    }
+}
+
+/* the buffer gets the 3 and then the digits without a point, up to its
+ * end even inside a block */
+#[test]
+fn fill_digits_fills_the_buffer() {
+    assert_eq!(parpi::fill_digits(&mut []), 0);
+    for len in [1, 2, 11, 100] {
+        let mut buf = vec![0; len];
+        assert_eq!(parpi::fill_digits(&mut buf), len);
+        let expected = pi_string(len as u32 - 1).replace('.', "");
+        assert_eq!(String::from_utf8(buf).unwrap(), expected, "{} bytes", len);
+    }
+}
+
+/* cancelled before it starts, no block is done and the result is the 3
+ * alone, as pi_string(0) gives */
+#[test]
+fn cancelled_before_the_start() {
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+
+    let cancel = Arc::new(AtomicBool::new(true));
+    assert_eq!(parpi::compute_cancellable(1000, cancel), "3");
+    let cancel = Arc::new(AtomicBool::new(false));
+    assert_eq!(parpi::compute_cancellable(10, cancel), pi_string(10));
+}
+
+/* cancelled while it runs, the result is pi up to a whole block, however
+ * many blocks were done by then */
+#[test]
+fn cancelled_while_running() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&cancel);
+    let canceller = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(200));
+        flag.store(true, Ordering::Relaxed);
+    });
+    /* a multiple of 9, so that a run done before the flag is set ends on a
+     * whole block too */
+    let pi = parpi::compute_cancellable(999, cancel);
+    canceller.join().unwrap();
+
+    if pi != "3" {
+        assert_eq!((pi.len() - 2) % 9, 0, "{:?}", pi);
+        assert_eq!(&pi[..2], "3.");
+        assert!(PI_2000.starts_with(&pi[2..]), "{:?}", pi);
+    }
+}
+
+/* every completed count is reported once, against the number of blocks,
+ * and the digits are those of pi_string */
+#[test]
+fn progress_reports_every_block_once() {
+    use std::sync::Mutex;
+
+    for digits in [0, 100, 101] {
+        let reports = Mutex::new(Vec::new());
+        let pi = parpi::compute_with_progress(digits, |done, total| {
+            reports.lock().unwrap().push((done, total));
+        });
+        assert_eq!(pi, pi_string(digits));
+
+        let mut reports = reports.into_inner().unwrap();
+        reports.sort_unstable();
+        let total = digits.div_ceil(9);
+        let expected: Vec<_> = (1..=total).map(|done| (done, total)).collect();
+        assert_eq!(reports, expected, "{} digits", digits);
+    }
+}
+
+/* the prefix and the streamed digits together are exactly pi_string,
+ * also when the count ends inside a block or is zero */
+#[test]
+fn write_pi_to_matches_pi_string() {
+    for digits in [0, 1, 9, 10, 100] {
+        let mut out = Vec::new();
+        parpi::write_pi_to(&mut out, digits).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            pi_string(digits),
+            "{} digits",
+            digits
+        );
+    }
+}
+
+#[test]
+fn pi_display_matches_pi_string() {
+    for digits in [0, 1, 9, 10, 100] {
+        let pi = parpi::Pi::with_digits(digits);
+        assert_eq!(pi.to_string(), pi_string(digits));
+        /* formatting again computes the same digits again */
+        assert_eq!(format!("{}", pi), pi_string(digits));
+    }
+}
+
+#[test]
+fn iterator_len_matches_items() {
+    for digits in [0, 1, 8, 9, 10, 40] {
+        let blocks = parpi::PiBlocks::with_digits(digits);
+        let len = blocks.len();
+        assert_eq!(blocks.count(), len, "{} digits", digits);
+
+        let mut stream = parpi::PiDigitStream::with_digits(digits);
+        assert_eq!(stream.len(), digits as usize + 1);
+        stream.next();
+        assert_eq!(stream.len(), digits as usize);
+        let rest: Vec<u8> = stream.collect();
+        assert_eq!(rest.len(), digits as usize, "{} digits", digits);
+        let text: String = rest.iter().map(|&d| (b'0' + d) as char).collect();
+        assert_eq!(text, &PI_2000[..digits as usize]);
+    }
+    assert_eq!(
+        parpi::PiBlocks::new().len(),
+        parpi::MAX_DIGITS.div_ceil(9) as usize
+    );
+}
+
+#[test]
+fn computed_pi_index() {
+    let pi = parpi::ComputedPi::with_digits(2000).unwrap();
+    assert_eq!(pi.len(), 2000);
+    /* out of order, revisiting blocks already computed */
+    for i in [1999, 0, 770, 8, 9, 1990, 771, 0, 1999] {
+        assert_eq!(pi[i], PI_2000.as_bytes()[i] - b'0', "digit {}", i);
+    }
+    assert_eq!(pi.get(2000), None);
+    assert!(std::ptr::eq(&pi[770], &pi[770]));
+    assert!(parpi::ComputedPi::with_digits(0).unwrap().is_empty());
+}
+
+/* a digit read again from the cache matches a fresh computation, and a
+ * full cache evicts the block used least recently */
+#[test]
+fn cached_pi_hits_and_evictions() {
+    let pi = parpi::CachedPi::with_capacity(2);
+    for i in [0, 5, 1999, 0, 1990] {
+        assert_eq!(pi.digit_at(i), parpi::pi_digit_at(i), "digit {}", i);
+        assert_eq!(pi.digit_at(i), PI_2000.as_bytes()[i as usize] - b'0');
+    }
+    /* blocks 0 and 221 were used last */
+    assert_eq!(pi.len(), 2);
+    assert!(pi.contains(3) && pi.contains(1990) && !pi.contains(1999));
+
+    pi.digit_at(900);
+    assert!(!pi.contains(0) && pi.contains(1990) && pi.contains(900));
+    pi.digit_at(1998);
+    pi.digit_at(9);
+    assert!(pi.contains(1998) && pi.contains(9) && !pi.contains(900));
+
+    let uncached = parpi::CachedPi::with_capacity(0);
+    assert_eq!(uncached.digit_at(770), PI_2000.as_bytes()[770] - b'0');
+    assert!(uncached.is_empty());
+}
+
+#[test]
+#[should_panic(expected = "beyond MAX_DIGITS")]
+fn cached_pi_index_past_max_digits() {
+    parpi::CachedPi::with_capacity(2).digit_at(parpi::MAX_DIGITS);
+}
+
+#[test]
+#[should_panic(expected = "out of range")]
+fn computed_pi_index_past_end() {
+    let pi = parpi::ComputedPi::with_digits(10).unwrap();
+    let _ = pi[10];
+}
+
+/* the last block of MAX_DIGITS looks ahead at the position right after
+ * it, which is as far as 25 * num_terms(n) still fits in an i32 */
+#[test]
+fn num_terms_known_values() {
+    use parpi::{num_terms, MAX_DIGITS};
+
+    assert_eq!(num_terms(1), 20);
+    assert_eq!(num_terms(10), 28);
+    assert_eq!(num_terms(1000), 906);
+    assert_eq!(num_terms(1_000_000), 884_720);
+
+    let last = MAX_DIGITS as i32 + 1;
+    assert!(num_terms(last).checked_mul(25).is_some());
+    assert!(num_terms(last + 9).checked_mul(25).is_none());
+}
+
+#[test]
+fn digit_sum_matches_digits() {
+    for digits in [0, 1, 8, 9, 10, 100, 1000, 2000] {
+        let expected: u64 = PI_2000[..digits as usize]
+            .bytes()
+            .map(|c| (c - b'0') as u64)
+            .sum();
+        assert_eq!(parpi::digit_sum(digits), expected, "{} digits", digits);
+    }
+}
@@ -0,0 +1,37 @@
+# Run with: maturin develop && pytest tests/python
+import threading
+import time
+
+import parpi
+import pytest
+
+
+def test_first_digits():
+    assert parpi.pi_string(0) == "3"
+    assert parpi.pi_string(10) == "3.1415926535"
+
+
+def test_digit_at_matches_string():
+    digits = parpi.pi_string(100)[2:]
+    assert [parpi.pi_digit_at(i) for i in range(100)] == [int(d) for d in digits]
+
+
+def test_past_max_digits_is_a_value_error():
+    # rejected before any digit is computed, instead of panicking in Rust
+    with pytest.raises(ValueError, match="too large"):
+        parpi.pi_string(parpi.MAX_DIGITS + 1)
+    with pytest.raises(ValueError, match="too large"):
+        parpi.pi_digit_at(parpi.MAX_DIGITS)
+
+
+def test_releases_the_gil():
+    # with the GIL held this thread would stall until the digits are done
+    worker = threading.Thread(target=parpi.pi_string, args=(1000,))
+    start = last = time.monotonic()
+    worker.start()
+    stall = 0.0
+    while worker.is_alive():
+        now = time.monotonic()
+        stall = max(stall, now - last)
+        last = now
+    assert stall < (last - start) / 2
@@ -0,0 +1,78 @@
+#![cfg(feature = "cli")]
+
+/*
+ * The exact output of a few small runs in each text format, kept in
+ * tests/snapshots. After an intentional change to the output, review the
+ * new snapshots with `cargo insta review` (from cargo-insta), or accept
+ * them all with INSTA_UPDATE=always.
+ */
+
+use std::process::Command;
+
+/* each run as the command line followed by what it printed */
+fn runs(runs: &[&[&str]]) -> String {
+    let mut text = String::new();
+    for args in runs {
+        let out = Command::new(env!("CARGO_BIN_EXE_parpi"))
+            .args(*args)
+            .env("NO_COLOR", "1")
+            .env("XDG_CONFIG_HOME", env!("CARGO_TARGET_TMPDIR"))
+            .output()
+            .unwrap();
+        assert!(out.status.success(), "parpi {:?}", args);
+        text += &format!("$ parpi {}\n", args.join(" "));
+        text += &String::from_utf8(out.stdout).unwrap();
+    }
+    text
+}
+
+#[test]
+fn decimal() {
+    insta::assert_snapshot!(runs(&[
+        &["0"],
+        &["1"],
+        &["9"],
+        &["10"],
+        &["50"],
+        &["20", "--no-prefix"],
+    ]));
+}
+
+#[test]
+fn raw() {
+    insta::assert_snapshot!(runs(&[
+        &["1", "--format", "raw"],
+        &["10", "--format", "raw"],
+        &["50", "--format", "raw"],
+        &["20", "--format", "base64"],
+    ]));
+}
+
+#[test]
+fn grouped() {
+    insta::assert_snapshot!(runs(&[
+        &["30", "--group", "5"],
+        &["50", "--group", "10"],
+        &["23", "--group", "9", "--format", "raw"],
+        &["10", "--group", "1"],
+    ]));
+}
+
+#[test]
+fn json() {
+    insta::assert_snapshot!(runs(&[
+        &["0", "--format", "json"],
+        &["20", "--format", "json"],
+        &["20", "--format", "jsonl"],
+    ]));
+}
+
+#[test]
+fn source() {
+    insta::assert_snapshot!(runs(&[
+        &["0", "--format", "rust"],
+        &["20", "--format", "rust"],
+        &["20", "--format", "c-header"],
+        &["10", "--format", "c-header", "--no-prefix"],
+    ]));
+}
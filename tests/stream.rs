@@ -0,0 +1,23 @@
+#![cfg(feature = "async")]
+
+use futures::executor::block_on;
+use futures::StreamExt;
+
+/* the blocks arrive in index order, the last one whole */
+#[test]
+fn blocks_arrive_in_order() {
+    let blocks: Vec<u32> = block_on(parpi::pi_stream(200).collect());
+    assert_eq!(blocks, parpi::compute_range(0, 23));
+    let blocks: Vec<u32> = block_on(parpi::pi_stream(0).collect());
+    assert!(blocks.is_empty());
+}
+
+/* a stream dropped early stops its work, so it does not hold on to the
+ * threads a later stream needs */
+#[test]
+fn dropped_stream_stops() {
+    let first: Vec<u32> = block_on(parpi::pi_stream(1_000_000).take(3).collect());
+    assert_eq!(first, parpi::compute_range(0, 3));
+    let blocks: Vec<u32> = block_on(parpi::pi_stream(20).collect());
+    assert_eq!(blocks, parpi::compute_range(0, 3));
+}
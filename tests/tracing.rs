@@ -0,0 +1,53 @@
+#![cfg(feature = "tracing")]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata};
+
+/* records the name of every span and counts the events */
+#[derive(Default)]
+struct Recorder {
+    spans: Mutex<Vec<&'static str>>,
+    events: AtomicUsize,
+}
+
+struct Subscriber(Arc<Recorder>);
+
+impl tracing::Subscriber for Subscriber {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut spans = self.0.spans.lock().unwrap();
+        spans.push(span.metadata().name());
+        Id::from_u64(spans.len() as u64)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, _: &Event<'_>) {
+        self.0.events.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+/* the blocks are computed on the rayon threads, so the subscriber has to
+ * be the global one */
+#[test]
+fn spans_and_events() {
+    let recorder = Arc::new(Recorder::default());
+    tracing::subscriber::set_global_default(Subscriber(recorder.clone())).unwrap();
+
+    assert_eq!(parpi::pi_string(20), "3.14159265358979323846");
+    let spans = recorder.spans.lock().unwrap();
+    assert_eq!(spans.iter().filter(|&&s| s == "digits").count(), 1);
+    assert_eq!(spans.iter().filter(|&&s| s == "block").count(), 3);
+    assert!(recorder.events.load(Ordering::Relaxed) >= 3);
+}
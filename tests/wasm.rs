@@ -0,0 +1,30 @@
+#![cfg(feature = "wasm")]
+
+/* the exported function also builds natively, where it can be tested */
+#[test]
+fn pi_matches_pi_string() {
+    let pi = |digits| parpi::wasm::pi(digits).unwrap();
+    assert_eq!(pi(0), "3");
+    assert_eq!(pi(10), "3.1415926535");
+    assert_eq!(pi(100), parpi::pi_string(100));
+}
+
+/*
+ * The error only exists as a JavaScript value, which cannot be made
+ * natively, so this runs on wasm32 only:
+ *
+ *   CARGO_TARGET_WASM32_UNKNOWN_UNKNOWN_RUNNER=wasm-bindgen-test-runner \
+ *       cargo test --target wasm32-unknown-unknown --no-default-features \
+ *       --features wasm --test wasm
+ */
+#[cfg(target_arch = "wasm32")]
+mod js {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn count_past_max_digits_is_an_error() {
+        assert!(parpi::wasm::pi(parpi::MAX_DIGITS + 1).is_err());
+        assert!(parpi::wasm::pi(u32::MAX).is_err());
+        assert_eq!(parpi::wasm::pi(5).unwrap(), "3.14159");
+    }
+}